@@ -0,0 +1,54 @@
+#![feature(test)]
+
+extern crate test;
+
+use mini_rx::*;
+use test::Bencher;
+
+/// About 10k elements: each variable is read by one computed value (an edge and a node)
+const NUM_VARS: usize = 3333;
+
+fn build<'c>(g: &RxDAG<'c>) -> Vec<Var<'c, usize>> {
+    let vars = (0..NUM_VARS).map(|i| g.new_var(i)).collect::<Vec<_>>();
+    for var in vars.iter().copied() {
+        g.new_crx(move |g| *var.get(g) + 1);
+    }
+    vars
+}
+
+/// One computed value recomputes, and it's at the end, so it collects its reads from the entire graph
+#[bench]
+fn bench_recompute_one_change(b: &mut Bencher) {
+    let mut g = RxDAG::new();
+    let vars = build(&g);
+    let last = *vars.last().unwrap();
+    let mut value = 0;
+    b.iter(|| {
+        value += 1;
+        last.set(&g, value);
+        g.recompute();
+    });
+}
+
+/// Every computed value recomputes
+#[bench]
+fn bench_recompute_all_change(b: &mut Bencher) {
+    let mut g = RxDAG::new();
+    let vars = build(&g);
+    let mut value = 0;
+    b.iter(|| {
+        value += 1;
+        for var in vars.iter() {
+            var.set(&g, value);
+        }
+        g.recompute();
+    });
+}
+
+/// Nothing changed, so this only checks every edge's inputs
+#[bench]
+fn bench_recompute_no_change(b: &mut Bencher) {
+    let mut g = RxDAG::new();
+    let _vars = build(&g);
+    b.iter(|| g.recompute());
+}
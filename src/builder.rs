@@ -0,0 +1,70 @@
+use std::alloc::{Allocator, Global};
+use std::ops::{Add, Sub, Mul, Div};
+use derivative::Derivative;
+use crate::dag::{RxDAG, RxContext};
+use crate::rx_ref::RxRef;
+
+/// Creates values in an [RxDAG] which support arithmetic operators. See [RxDAG::build].
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""))]
+pub struct RxBuilder<'a, 'c: 'a, A: Allocator + 'c = Global>(&'a RxDAG<'c, A>);
+
+/// A [Var](crate::Var) or [CRx](crate::CRx) created by an [RxBuilder].
+///
+/// `a + b`, `a - b`, `a * b` and `a / b` create a new [CRx](crate::CRx) in the same DAG.
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""))]
+pub struct BuiltRx<'a, 'c: 'a, T, A: Allocator + 'c = Global> {
+    graph: &'a RxDAG<'c, A>,
+    rx: RxRef<'c, T, A>
+}
+
+impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
+    /// Build part of the graph using operators on values, e.g. `g.build(|b| (b.var(1) + b.var(2)) * b.var(3))`
+    /// instead of `g.new_crx(move |g| ...)`.
+    pub fn build<'a, R>(&'a self, build: impl FnOnce(RxBuilder<'a, 'c, A>) -> R) -> R {
+        build(RxBuilder(self))
+    }
+}
+
+impl<'a, 'c: 'a, A: Allocator + Clone + 'c> RxBuilder<'a, 'c, A> {
+    /// Create a variable. If you need to set it, create it with [RxDAG::new_var] and [RxBuilder::wrap] it instead.
+    pub fn var<T: 'c>(self, init: T) -> BuiltRx<'a, 'c, T, A> {
+        self.wrap(self.0.new_var(init).raw())
+    }
+
+    /// Wrap an existing [Var](crate::Var) or [CRx](crate::CRx) in the DAG so it supports operators.
+    pub fn wrap<T>(self, rx: RxRef<'c, T, A>) -> BuiltRx<'a, 'c, T, A> {
+        BuiltRx { graph: self.0, rx }
+    }
+}
+
+impl<'a, 'c: 'a, T, A: Allocator + 'c> BuiltRx<'a, 'c, T, A> {
+    /// Get the underlying reference
+    pub fn raw(self) -> RxRef<'c, T, A> {
+        self.rx
+    }
+
+    /// Read the value
+    pub fn get<'b>(self, c: impl RxContext<'b, 'c, A>) -> &'b T where 'c: 'b {
+        self.rx.get(c)
+    }
+}
+
+macro impl_bin_op($Op:ident, $op:ident) {
+    impl<'a, 'c: 'a, T: $Op<U> + Clone + 'c, U: Clone + 'c, A: Allocator + Clone + 'c> $Op<BuiltRx<'a, 'c, U, A>> for BuiltRx<'a, 'c, T, A> where T::Output: 'c {
+        type Output = BuiltRx<'a, 'c, T::Output, A>;
+
+        fn $op(self, rhs: BuiltRx<'a, 'c, U, A>) -> Self::Output {
+            debug_assert!(std::ptr::eq(self.graph, rhs.graph), "operands are from different DAGs");
+            let (lhs, rhs) = (self.rx, rhs.rx);
+            let result = self.graph.new_crx(move |g| lhs.get(g).clone().$op(rhs.get(g).clone()));
+            RxBuilder(self.graph).wrap(result.raw())
+        }
+    }
+}
+
+impl_bin_op!(Add, add);
+impl_bin_op!(Sub, sub);
+impl_bin_op!(Mul, mul);
+impl_bin_op!(Div, div);
@@ -0,0 +1,57 @@
+use std::alloc::Allocator;
+use crate::rx_ref::UntypedRxRef;
+
+/// Which inputs of a computed value changed since it was last computed.
+///
+/// Inputs are ordered by their position in the [RxDAG](crate::RxDAG), i.e. the order they were created in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedInputs {
+    indices: Vec<usize>,
+    changed: Vec<bool>
+}
+
+impl ChangedInputs {
+    pub(crate) fn clear(&mut self) {
+        self.indices.clear();
+        self.changed.clear();
+    }
+
+    pub(crate) fn push(&mut self, index: usize, changed: bool) {
+        debug_assert!(self.indices.last().is_none_or(|last| *last < index), "ChangedInputs must be pushed in order");
+        self.indices.push(index);
+        self.changed.push(changed);
+    }
+
+    /// Number of inputs
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Whether there are no inputs
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Whether the `i`th input changed. Panics if out of bounds.
+    pub fn get(&self, i: usize) -> bool {
+        self.changed[i]
+    }
+
+    /// Whether `input` is an input and changed.
+    pub fn contains<'c, A: Allocator>(&self, input: UntypedRxRef<'c, A>) -> bool {
+        match self.indices.binary_search(&input.index()) {
+            Ok(i) => self.changed[i],
+            Err(_) => false
+        }
+    }
+
+    /// Whether any input changed
+    pub fn any(&self) -> bool {
+        self.changed.iter().any(|changed| *changed)
+    }
+
+    /// Iterate whether each input changed
+    pub fn iter(&self) -> impl Iterator<Item=bool> + '_ {
+        self.changed.iter().copied()
+    }
+}
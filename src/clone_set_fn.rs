@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::marker::PhantomData;
 
 #[doc(hidden)]
@@ -7,6 +8,11 @@ impl<T: Clone, U, F: Fn(&mut T, U)> CloneSetFn<T, U, F> {
     pub(crate) fn new(f: F) -> CloneSetFn<T, U, F> {
         CloneSetFn(f, PhantomData)
     }
+
+    /// Set the part in place, without cloning
+    pub(crate) fn set_in_place(&self, root: &mut T, child: U) {
+        self.0(root, child)
+    }
 }
 
 impl<T: Clone, U, F: Fn(&mut T, U)> FnOnce<(&T, U)> for CloneSetFn<T, U, F> {
@@ -35,3 +41,43 @@ impl<T: Clone, U, F: Fn(&mut T, U)> Fn<(&T, U)> for CloneSetFn<T, U, F> {
     }
 }
 
+
+#[doc(hidden)]
+pub struct CowSetFn<T: Clone, U, F: Fn(&mut Cow<'_, T>, U)>(F, PhantomData<(T, U)>);
+
+impl<T: Clone, U, F: Fn(&mut Cow<'_, T>, U)> CowSetFn<T, U, F> {
+    pub(crate) fn new(f: F) -> CowSetFn<T, U, F> {
+        CowSetFn(f, PhantomData)
+    }
+
+    /// Set the part, cloning the root only if the setter mutates it
+    pub(crate) fn set_cow(&self, root: &mut Cow<'_, T>, child: U) {
+        self.0(root, child)
+    }
+}
+
+impl<T: Clone, U, F: Fn(&mut Cow<'_, T>, U)> FnOnce<(&T, U)> for CowSetFn<T, U, F> {
+    type Output = T;
+
+    extern "rust-call" fn call_once(self, (root, child): (&T, U)) -> T {
+        let mut root = Cow::Borrowed(root);
+        self.0(&mut root, child);
+        root.into_owned()
+    }
+}
+
+impl<T: Clone, U, F: Fn(&mut Cow<'_, T>, U)> FnMut<(&T, U)> for CowSetFn<T, U, F> {
+    extern "rust-call" fn call_mut(&mut self, (root, child): (&T, U)) -> T {
+        let mut root = Cow::Borrowed(root);
+        self.0(&mut root, child);
+        root.into_owned()
+    }
+}
+
+impl<T: Clone, U, F: Fn(&mut Cow<'_, T>, U)> Fn<(&T, U)> for CowSetFn<T, U, F> {
+    extern "rust-call" fn call(&self, (root, child): (&T, U)) -> T {
+        let mut root = Cow::Borrowed(root);
+        self.0(&mut root, child);
+        root.into_owned()
+    }
+}
@@ -0,0 +1,100 @@
+use std::alloc::{Allocator, Global};
+use std::rc::Rc;
+use derivative::Derivative;
+use crate::dag::{RxDAG, RxContext, RxInput, RxSubDAG};
+use crate::readable::Readable;
+use crate::rx_impl::{RxDAGElem, RxDAGElemRef, RxEdgeImpl, RxImpl, Rx};
+use crate::rx_ref::CRx;
+
+/// A computed value which [RxDAG::compact] frees once every clone of this handle is dropped
+/// and no other computation reads it. See [RxDAG::new_owned_crx].
+///
+/// Unlike [CRx] this isn't [Copy], because the graph must know when the handles are gone.
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct OwnedCRx<'c, T, A: Allocator = Global> {
+    crx: CRx<'c, T, A>,
+    // The graph holds a weak reference
+    _handle: Rc<()>
+}
+
+impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
+    /// Create a computed value which can be freed by [RxDAG::compact] once it's no longer used.
+    pub fn new_owned_crx<T: 'c>(&self, compute: impl FnMut(RxInput<'_, 'c, A>) -> T + 'c) -> OwnedCRx<'c, T, A> {
+        let crx = self.new_crx(compute);
+        let handle = Rc::new(());
+        self.collectable().borrow_mut().push((crx.raw().raw().index(), Rc::downgrade(&handle)));
+        OwnedCRx { crx, _handle: handle }
+    }
+
+    /// Free every computed value from [RxDAG::new_owned_crx] whose handles are all dropped,
+    /// and which isn't read by any other computation (unless that computation is also freed).
+    /// Returns how many values were freed.
+    ///
+    /// The values and their computations are dropped, but they stay in the graph as empty placeholders
+    /// so the positions of other nodes don't change. The placeholders are never recomputed.
+    pub fn compact(&mut self) -> usize {
+        // How many computations read each node
+        let mut num_readers = vec![0usize; self.elems().len()];
+        for (index, elem) in self.elems().iter().enumerate() {
+            if let RxDAGElemRef::Edge(edge) = elem {
+                for offset in edge.input_backwards_offsets() {
+                    num_readers[index - offset] += 1;
+                }
+            }
+        }
+
+        let mut collectable = std::mem::take(&mut *self.collectable().borrow_mut());
+        let mut num_freed = 0;
+        // Inputs are always before, so going backwards we free nodes only read by other freed nodes in one pass
+        for (index, handle) in collectable.iter_mut().rev() {
+            if handle.strong_count() > 0 || num_readers[*index] > 0 {
+                continue;
+            }
+            // Owned values are always the only output of the edge right before them
+            let edge_index = *index - 1;
+            let input_offsets = match self.elems().get(edge_index) {
+                Some(RxDAGElemRef::Edge(edge)) => edge.input_backwards_offsets().to_vec(),
+                _ => unreachable!("owned value isn't preceded by its edge")
+            };
+            for offset in input_offsets {
+                num_readers[edge_index - offset] -= 1;
+            }
+            let placeholder_edge = RxEdgeImpl::<'c, _, A>::new(Vec::new(), 1, |_: &mut Vec<usize>, _: RxInput<'_, 'c, A>, _: &mut dyn Iterator<Item=&Rx<'c, A>>| {});
+            let placeholder_edge = RxDAGElem::Edge(self.new_box(placeholder_edge));
+            let placeholder_node = RxDAGElem::Node(self.new_box(RxImpl::<(), A>::new(())));
+            let elems = self.elems_mut();
+            elems[edge_index] = placeholder_edge;
+            elems[*index] = placeholder_node;
+            // The placeholder has a different type, so the registered cloner and formatter must not be used on it
+            self.dynamic_cloners().borrow_mut().remove(index);
+            self.debug_formatters().borrow_mut().remove(index);
+            // The placeholder wasn't read
+            self.read_flags().remove(*index);
+            // Mark freed so it's removed below
+            *index = usize::MAX;
+            num_freed += 1;
+        }
+        collectable.retain(|(index, _)| *index != usize::MAX);
+        // Nothing can create owned values while we have `&mut self`
+        *self.collectable().borrow_mut() = collectable;
+        num_freed
+    }
+}
+
+impl<'c, T, A: Allocator + 'c> OwnedCRx<'c, T, A> {
+    /// Read the computed value
+    pub fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        self.crx.get(c)
+    }
+}
+
+impl<'c, T, A: Allocator + 'c> Readable<'c, T, A> for OwnedCRx<'c, T, A> {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        self.crx.get(c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        self.crx.raw().get_untracked(graph)
+    }
+}
@@ -1,12 +1,34 @@
 use std::alloc::{Allocator, Global};
-use std::fmt::{Debug, Formatter};
+use std::any::{Any, type_name};
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::iter::Sum;
+use std::time::{Duration, Instant};
 use derivative::Derivative;
 use crate::dag_uid::RxDAGUid;
-use crate::rx_impl::{RxDAGElem, RxImpl, Rx, RxEdgeImpl};
-use crate::rx_ref::{RxRef, Var, CRx};
+use crate::rx_impl::{RxDAGElem, RxImpl, Rx, RxEdgeImpl, LazyRxImpl, LazyCompute, CowRxImpl, CowValue, InitialRxImpl};
+use crate::rx_ref::{RxRef, Var, CRx, CRxGroup, UntypedRxRef};
 use crate::misc::frozen_vec::{FrozenVec, FrozenSlice};
+use crate::misc::flag_set::FlagSet;
 use crate::misc::assert_variance::assert_is_covariant;
 use crate::misc::slice_split3::SliceSplit3;
+use crate::changed_inputs::ChangedInputs;
+use crate::readable::Readable;
+use crate::stepper::RecomputeStepper;
+use crate::volatility::VolatilityTracker;
+use crate::epoch::Epoch;
+use crate::dynamic::DynamicEntry;
+use crate::explain::DebugEntry;
+use crate::history::NodeHistory;
+use crate::on_set::{NotifySet, SetListeners};
+use crate::external::ExternalInput;
 
 /// Returns a slice of [RxDAG] you can read nodes from.
 ///
@@ -37,7 +59,8 @@ pub trait MutRxContext<'a, 'c: 'a, A: Allocator = Global> {
 /// ## Performance notes
 ///
 /// Currently no nodes ([Var]s or [CRx]s) are deallocated until the entire DAG is deallocated,
-/// so if you keep creating and discarding nodes you will leak memory (TODO fix this?)
+/// so if you keep creating and discarding nodes you will leak memory (TODO fix this?).
+/// Computed values created by [RxDAG::new_owned_crx] can have their values freed by [RxDAG::compact].
 ///
 /// ## Implementation
 ///
@@ -59,14 +82,76 @@ pub trait MutRxContext<'a, 'c: 'a, A: Allocator = Global> {
 ///
 /// The DAG and refs have an ID so that you can't use one ref on another DAG, however this is checked at runtime.
 /// The lifetimes are checked at compile-time though.
-pub struct RxDAG<'c, A: Allocator = Global>(FrozenVec<RxDAGElem<'c, A>, A>, RxDAGUid<'c, A>, A);
+pub struct RxDAG<'c, A: Allocator = Global> {
+    elems: FrozenVec<RxDAGElem<'c, A>, A>,
+    id: RxDAGUid<'c, A>,
+    mismatch_policy: MismatchPolicy,
+    alloc: A,
+    // Indices of nodes which got recomputed and edges which got deferred in the current recompute,
+    // so that we only visit those instead of the entire DAG afterwards
+    recomputed: Vec<usize>,
+    // Incremented on every recompute
+    revision: u64,
+    // Whether a variable was set since the last recompute
+    dirty: Cell<bool>,
+    // Which nodes were read since a computation last collected its reads, so computations discover their inputs.
+    // They're grouped in one bitset so collecting them doesn't visit every node
+    read_flags: FlagSet,
+    warn_on_stale_read: Cell<bool>,
+    stale_reads: Cell<usize>,
+    volatility: Option<VolatilityTracker>,
+    // Set between [RxDAG::begin_epoch] and [RxDAG::end_epoch]
+    epoch: Option<Epoch>,
+    // While suspended, `now` doesn't recompute
+    suspended: bool,
+    // Nodes registered by [RxDAG::register_dynamic], and how to clone their values
+    dynamic_cloners: RefCell<HashMap<usize, DynamicEntry>>,
+    // Nodes registered by [RxDAG::register_debug], and how to format their values
+    debug_formatters: RefCell<HashMap<usize, DebugEntry>>,
+    // Nodes created by [RxDAG::new_owned_crx] which [RxDAG::compact] may free, and whether their handles are alive
+    collectable: RefCell<Vec<(usize, Weak<()>)>>,
+    // Revision each element last changed, or when it was created if it hasn't changed since.
+    // Elements created since the last recompute aren't included
+    last_changes: Vec<u64>,
+    // Nodes whose values are recorded by [RxDAG::track_history]
+    history: RefCell<HashMap<usize, NodeHistory>>,
+    set_listeners: SetListeners<'c>,
+    // Variables created by [RxDAG::new_external_input], which are set at the start of each recompute
+    external_inputs: RefCell<Vec<ExternalInput<'c, A>>>,
+    // When [RxDAG::recompute_if_elapsed] last recomputed
+    last_throttled_recompute: Option<Instant>,
+    // See [RxDAG::with_max_elements]
+    max_elements: Option<usize>,
+    // Callbacks from [RxDAG::after_next_recompute], run once at the end of the next recompute
+    after_recompute: RefCell<Vec<Box<dyn FnOnce() + 'c>>>
+}
 
 impl<'c, A: Allocator + Debug + 'c> Debug for RxDAG<'c, A> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("RxDAG")
-            .field(&self.0)
-            .field(&self.1)
-            .field(&self.2)
+            .field(&self.elems)
+            .field(&self.id)
+            .field(&self.mismatch_policy)
+            .field(&self.alloc)
+            .field(&self.recomputed)
+            .field(&self.revision)
+            .field(&self.dirty.get())
+            .field(&self.read_flags)
+            .field(&self.warn_on_stale_read.get())
+            .field(&self.stale_reads.get())
+            .field(&self.volatility)
+            .field(&self.epoch)
+            .field(&self.suspended)
+            .field(&self.dynamic_cloners.borrow().keys())
+            .field(&self.debug_formatters.borrow().keys())
+            .field(&self.collectable)
+            .field(&self.last_changes)
+            .field(&self.history)
+            .field(&self.set_listeners)
+            .field(&self.external_inputs.borrow().len())
+            .field(&self.last_throttled_recompute)
+            .field(&self.max_elements)
+            .field(&self.after_recompute.borrow().len())
             .finish()
     }
 }
@@ -76,6 +161,14 @@ impl<'c, A: Allocator + Debug + 'c> Debug for RxDAG<'c, A> {
 #[derivative(Clone(bound = ""), Copy(bound = ""))]
 pub struct RxDAGSnapshot<'a, 'c: 'a, A: Allocator + 'c = Global>(&'a RxDAG<'c, A>);
 
+/// Allows you to read from an [RxDAG], where every read is from the same revision. See [RxDAG::read_consistent].
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""), Copy(bound = ""))]
+pub struct ConsistentView<'a, 'c: 'a, A: Allocator + 'c = Global> {
+    snapshot: RxDAGSnapshot<'a, 'c, A>,
+    revision: u64
+}
+
 /// Slice of an [RxDAG]
 #[doc(hidden)]
 #[derive(Debug, Derivative)]
@@ -83,7 +176,20 @@ pub struct RxDAGSnapshot<'a, 'c: 'a, A: Allocator + 'c = Global>(&'a RxDAG<'c, A
 pub struct RxSubDAG<'a, 'c: 'a, A: Allocator = Global> {
     pub(crate) before: FrozenSlice<'a, RxDAGElem<'c, A>>,
     pub(crate) index: usize,
-    pub(crate) id: RxDAGUid<'c, A>
+    pub(crate) id: RxDAGUid<'c, A>,
+    pub(crate) mismatch_policy: MismatchPolicy,
+    // Number of outputs of the computation at `index`, or 0 if this isn't a computation's view
+    pub(crate) num_outputs: usize,
+    // Set when a value is set, including from inside a computation, so the graph knows it must recompute.
+    // The reference is copied: otherwise derivative would clone the `Cell`
+    #[derivative(Clone(clone_with = "Clone::clone"))]
+    pub(crate) dirty: &'a Cell<bool>,
+    // Which nodes were read since a computation last collected its reads
+    pub(crate) read_flags: &'a FlagSet,
+    // Set when reading a stale snapshot of a dirty graph with [RxDAG::warn_on_stale_read] enabled
+    pub(crate) stale_reads: Option<&'a Cell<usize>>,
+    // Listeners to call when a variable is set through this slice, only when setting through the [RxDAG]
+    pub(crate) set_listeners: Option<&'a dyn NotifySet>
 }
 assert_is_covariant!(for['a, A: Allocator]['a, A] (RxSubDAG<'a, 'c, A>) over 'c);
 
@@ -92,24 +198,126 @@ assert_is_covariant!(for['a, A: Allocator]['a, A] (RxSubDAG<'a, 'c, A>) over 'c)
 #[derivative(Clone(bound = ""), Copy(bound = ""))]
 pub struct RxInput<'a, 'c: 'a, A: Allocator = Global>(pub(crate) RxSubDAG<'a, 'c, A>);
 
+/// What happens when you use a ref from a different [RxDAG]. See [RxDAG::set_mismatch_policy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MismatchPolicy {
+    /// Only checked in debug builds (panics). In release builds using a ref from a different graph is UB
+    #[default]
+    None,
+    /// Always checked, panics
+    Panic,
+    /// Always checked, logs an error and skips what it can: sets are ignored, reads which can fail ([RxRef::try_get]) return `None`,
+    /// and introspection returns nothing (e.g. [RxDAG::dependencies_of] is empty).
+    /// Reads which must return a value ([RxRef::get]) still panic afterwards
+    Log
+}
+
+/// When a computed value computes. See [RxDAG::new_crx_with].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Laziness {
+    /// Compute on creation and whenever inputs change, like [RxDAG::new_crx]
+    #[default]
+    Eager,
+    /// Compute when first read, then whenever inputs change
+    Lazy,
+    /// Compute when first read, then when read after inputs change
+    OnDemand
+}
+
+/// Builds a chain of computed values. See [RxDAG::new_crx_pipeline].
+#[derive(Debug)]
+pub struct CRxPipeline<'a, 'c: 'a, T, A: Allocator + 'c = Global> {
+    graph: &'a RxDAG<'c, A>,
+    outputs: Vec<CRx<'c, T, A>>
+}
+
+/// Returned by [RxDAG::try_new_var] and [RxDAG::try_new_crx] when the DAG has its maximum number of elements.
+/// See [RxDAG::with_max_elements].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityExceeded {
+    /// The maximum number of elements
+    pub max: usize
+}
+
+/// Returned by [RxDAG::new_crx_strict] when the computation doesn't read any values, so it would never recompute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoInputs;
+
+/// Returned by [RxDAG::recompute_catching] when a computation panics.
+#[derive(Debug)]
+pub struct EdgePanic {
+    /// Index of the edge in the DAG
+    pub index: usize,
+    /// The value the computation panicked with
+    pub payload: Box<dyn Any + Send>
+}
+
 impl<'c> RxDAG<'c> {
     /// Create and empty DAG
     pub fn new() -> Self {
         Self::new_in(Global)
     }
+
+    /// Create an empty DAG which can only have `max` elements (each variable is 1 element, each computed value or
+    /// side-effect is 1 more for its edge). Use this when building graphs from untrusted input.
+    ///
+    /// [RxDAG::try_new_var] and [RxDAG::try_new_crx] return [CapacityExceeded] instead of creating more elements,
+    /// and other constructors panic.
+    pub fn with_max_elements(max: usize) -> Self {
+        let mut graph = Self::new();
+        graph.set_max_elements(Some(max));
+        graph
+    }
+
+    /// Create a DAG and build your model in it: `init` creates the nodes and returns them in a struct,
+    /// which is returned alongside the DAG so you can keep both together.
+    pub fn scope<S>(init: impl FnOnce(&Self) -> S) -> (Self, S) {
+        Self::scope_in(Global, init)
+    }
 }
 
 impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
     /// Create an empty DAG in the specified allocator.
     pub fn new_in(alloc: A) -> Self {
-        Self(FrozenVec::new_in(alloc.clone()), RxDAGUid::next(), alloc)
+        Self {
+            elems: FrozenVec::new_in(alloc.clone()),
+            id: RxDAGUid::next(),
+            mismatch_policy: MismatchPolicy::default(),
+            alloc,
+            recomputed: Vec::new(),
+            revision: 0,
+            dirty: Cell::new(false),
+            read_flags: FlagSet::new(),
+            warn_on_stale_read: Cell::new(false),
+            stale_reads: Cell::new(0),
+            volatility: None,
+            epoch: None,
+            suspended: false,
+            dynamic_cloners: RefCell::new(HashMap::new()),
+            debug_formatters: RefCell::new(HashMap::new()),
+            collectable: RefCell::new(Vec::new()),
+            last_changes: Vec::new(),
+            history: RefCell::new(HashMap::new()),
+            set_listeners: SetListeners::default(),
+            external_inputs: RefCell::new(Vec::new()),
+            last_throttled_recompute: None,
+            max_elements: None,
+            after_recompute: RefCell::new(Vec::new())
+        }
+    }
+
+    /// Create a DAG in the specified allocator and build your model in it. See [RxDAG::scope].
+    pub fn scope_in<S>(alloc: A, init: impl FnOnce(&Self) -> S) -> (Self, S) {
+        let graph = Self::new_in(alloc);
+        let model = init(&graph);
+        (graph, model)
     }
 
     fn alloc(&self) -> A {
-        self.2.clone()
+        self.alloc.clone()
     }
 
-    fn new_box<T>(&self, inner: T) -> Box<T, A> {
+    pub(crate) fn new_box<T>(&self, inner: T) -> Box<T, A> {
         Box::new_in(inner, self.alloc())
     }
 
@@ -117,46 +325,392 @@ impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
     pub fn new_var<T: 'c>(&self, init: T) -> Var<'c, T, A> {
         let index = self.next_index();
         let rx = RxImpl::new(init);
-        self.0.push(RxDAGElem::Node(Box::new_in(rx, self.alloc())));
+        self.push_elem(RxDAGElem::Node(Box::new_in(rx, self.alloc())));
         Var::new(RxRef::new(self, index))
     }
 
+    /// Create a variable ([Var]) in this DAG which keeps its initial value, so you can read it with [Var::initial].
+    pub fn new_var_with_initial<T: Clone + 'c>(&self, init: T) -> Var<'c, T, A> {
+        let index = self.next_index();
+        let rx = InitialRxImpl::new(init);
+        self.push_elem(RxDAGElem::Node(self.new_box(rx)));
+        Var::new(RxRef::new(self, index))
+    }
+
+    /// Create a constant computed value ([CRx]) in this DAG, which never changes.
+    ///
+    /// This is useful to pass a constant to something which takes a [CRx].
+    pub fn constant<T: 'c>(&self, value: T) -> CRx<'c, T, A> {
+        // Like a variable which is never set
+        let index = self.next_index();
+        let rx = RxImpl::new(value);
+        self.push_elem(RxDAGElem::Node(self.new_box(rx)));
+        CRx::new(RxRef::new(self, index))
+    }
+
     // region new_crx boilerplate
 
     /// Run a closure when inputs change, without creating any outputs (for side-effects).
     pub fn run_crx<F: FnMut(RxInput<'_, 'c, A>) + 'c>(&self, mut compute: F) {
         let mut input_backwards_offsets = Vec::new();
-        let () = Self::run_compute(&mut compute, RxInput(self.sub_dag()), &mut input_backwards_offsets);
+        let () = Self::run_compute(&mut compute, self.creation_input(0), &mut input_backwards_offsets);
         let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 0, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
             input_backwards_offsets.clear();
             let () = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
             debug_assert!(outputs.next().is_none());
         });
-        self.0.push(RxDAGElem::Edge(self.new_box(compute_edge)));
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+    }
+
+    /// Run a side-effect when inputs change, but not now.
+    ///
+    /// To know the inputs, the computation has to run when it's created, so it's split in two:
+    /// `read` reads the inputs and runs every time, and `effect` gets its result and runs every time but the first.
+    pub fn run_crx_skip_initial<T: 'c>(&self, mut read: impl FnMut(RxInput<'_, 'c, A>) -> T + 'c, mut effect: impl FnMut(T) + 'c) {
+        let mut first = true;
+        self.run_crx(move |g| {
+            let value = read(g);
+            if first {
+                first = false;
+            } else {
+                effect(value);
+            }
+        });
+    }
+
+    /// Run a side-effect with `source`'s value now and whenever it changes to a value it never had before.
+    /// Unlike skipping when the value doesn't change, returning to a value it had earlier doesn't run `f` again.
+    ///
+    /// Every distinct value is kept for as long as the DAG exists, so don't use this on a value with unbounded variety.
+    pub fn run_crx_distinct<T: Eq + Hash + Clone + 'c>(&self, source: CRx<'c, T, A>, mut f: impl FnMut(&T) + 'c) {
+        let mut seen = HashSet::new();
+        self.run_crx(move |g| {
+            let value = source.get(g);
+            if !seen.contains(value) {
+                seen.insert(value.clone());
+                f(value);
+            }
+        });
+    }
+
+    /// Run a closure with the values of all `refs` now and whenever any of them change (for side-effects).
+    ///
+    /// `f` runs once per recompute no matter how many of the values changed.
+    pub fn watch<T: 'c>(&self, refs: &[CRx<'c, T, A>], mut f: impl FnMut(&[&T]) + 'c) {
+        let refs = refs.to_vec();
+        self.run_crx(move |g| {
+            let values = refs.iter().map(|rx| rx.get(g)).collect::<Vec<_>>();
+            f(&values);
+        });
+    }
+
+    /// Exchange the values of `a` and `b`. Like `set` the changes only actually reflect on recompute.
+    ///
+    /// If either variable was set since the last recompute, its latest value is swapped.
+    /// Swapping a variable with itself does nothing.
+    pub fn swap<T: Clone + 'c>(&self, a: Var<'c, T, A>, b: Var<'c, T, A>) {
+        if a.same_node(b) {
+            return;
+        }
+        let a_value = a.raw().take_latest(self);
+        let b_value = b.raw().take_latest(self);
+        a.set(self, b_value);
+        b.set(self, a_value);
+    }
+
+    /// Keep `a` and `b` equal: when one is set, the other is set to the same value.
+    ///
+    /// The other variable gets the value on the *next* recompute, so after setting one it takes 2 recomputes
+    /// for both to be updated. Values are only copied when they differ, which stops the link from ping-ponging.
+    /// If both are set before the same recompute, `a`'s value wins.
+    pub fn link_vars<T: Clone + PartialEq + 'c>(&self, a: Var<'c, T, A>, b: Var<'c, T, A>) {
+        self.link_var_to(a, b, false);
+        self.link_var_to(b, a, true);
+    }
+
+    fn link_var_to<T: Clone + PartialEq + 'c>(&self, source: Var<'c, T, A>, target: Var<'c, T, A>, yield_to_target: bool) {
+        self.run_crx_reading_previous(move |g| {
+            let value = source.get(g);
+            if yield_to_target && target.raw().previous(g).is_some() {
+                // Target also changed this recompute, and it wins
+                return;
+            }
+            if g.get_untracked(target) != value {
+                target.raw().set_in(g.0, value.clone());
+            }
+        });
+    }
+
+    /// Run a closure when inputs change, without creating any outputs (for side-effects),
+    /// and tag it so [RxDAG::recompute_tag] can run only the side-effects with this tag.
+    ///
+    /// Regular recomputes run tagged side-effects like any other.
+    pub fn run_crx_tagged<F: FnMut(RxInput<'_, 'c, A>) + 'c>(&self, tag: u32, mut compute: F) {
+        let mut input_backwards_offsets = Vec::new();
+        let () = Self::run_compute(&mut compute, self.creation_input(0), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 0, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let () = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            debug_assert!(outputs.next().is_none());
+        }).tagged(tag);
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+    }
+
+    /// [RxDAG::run_crx] for side-effects which read their inputs' previous values (see [RxRef::previous]).
+    /// Previous values are gone after the recompute where the inputs changed,
+    /// so [RxDAG::recompute_tag] and [RxDAG::evaluate] run these side-effects instead of postponing them.
+    pub(crate) fn run_crx_reading_previous<F: FnMut(RxInput<'_, 'c, A>) + 'c>(&self, mut compute: F) {
+        let mut input_backwards_offsets = Vec::new();
+        let () = Self::run_compute(&mut compute, self.creation_input(0), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 0, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let () = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            debug_assert!(outputs.next().is_none());
+        }).reads_previous();
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+    }
+
+    /// Run a closure when inputs change, but only after the entire DAG has recomputed (for side-effects).
+    ///
+    /// Regular side-effects from [RxDAG::run_crx] run in the middle of [RxDAG::recompute], in the order they were created.
+    /// Deferred side-effects run after every value has been updated, so they are useful for e.g. a render flush
+    /// which needs everything to be settled. Deferred side-effects run in the order they were created.
+    pub fn run_crx_deferred<F: FnMut(RxInput<'_, 'c, A>) + 'c>(&self, mut compute: F) {
+        let mut input_backwards_offsets = Vec::new();
+        let () = Self::run_compute(&mut compute, self.creation_input(0), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 0, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let () = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            debug_assert!(outputs.next().is_none());
+        }).deferred();
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+    }
+
+    /// Create a variable ([Var]) in this DAG, or return [CapacityExceeded] if it's full (see [RxDAG::with_max_elements]).
+    pub fn try_new_var<T: 'c>(&self, init: T) -> Result<Var<'c, T, A>, CapacityExceeded> {
+        self.check_capacity(1)?;
+        Ok(self.new_var(init))
+    }
+
+    /// Create a computed value ([CRx]) in this DAG, or return [CapacityExceeded] if it's full (see [RxDAG::with_max_elements]).
+    /// If it's full, `compute` doesn't run.
+    pub fn try_new_crx<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, compute: F) -> Result<CRx<'c, T, A>, CapacityExceeded> {
+        self.check_capacity(2)?;
+        Ok(self.new_crx(compute))
     }
 
     /// Create a computed value ([CRx]) in this DAG.
     pub fn new_crx<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, mut compute: F) -> CRx<'c, T, A> {
         let mut input_backwards_offsets = Vec::new();
-        let init = Self::run_compute(&mut compute, RxInput(self.sub_dag()), &mut input_backwards_offsets);
+        let init = Self::run_compute(&mut compute, self.creation_input(1), &mut input_backwards_offsets);
+        self.push_compute_edge(input_backwards_offsets, compute, init)
+    }
+
+    /// Create a computed value ([CRx]) whose computation gets its current value, and returns `None` to keep it.
+    /// Then the value doesn't change, so its dependents don't recompute.
+    ///
+    /// This lets a computation read some inputs and skip the expensive part if nothing it cares about changed.
+    /// On creation the current value is `init`.
+    pub fn new_crx_retain<T: 'c, F: FnMut(RxInput<'_, 'c, A>, &T) -> Option<T> + 'c>(&self, init: T, mut compute: F) -> CRx<'c, T, A> {
+        let mut input_backwards_offsets = Vec::new();
+        let first = Self::run_compute(&mut |input| compute(input, &init), self.creation_input(1), &mut input_backwards_offsets);
+        let init = first.unwrap_or(init);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let output = outputs.next().unwrap();
+            let current = unsafe { output.get_dyn::<T>() };
+            if let Some(next) = Self::run_compute(&mut |input| compute(input, current), input, &mut input_backwards_offsets) {
+                unsafe { output.set_dyn(next); }
+            }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_crx_edge(compute_edge, init)
+    }
+
+    /// Create a computed value ([CRx]) in this DAG, or return [NoInputs] if `compute` doesn't read any values.
+    ///
+    /// A computed value with no inputs never recomputes, which is usually a bug (e.g. reading a value without `get`).
+    /// If it's intentional, use [RxDAG::constant]. On error, nothing is added to the DAG.
+    pub fn new_crx_strict<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, mut compute: F) -> Result<CRx<'c, T, A>, NoInputs> {
+        let mut input_backwards_offsets = Vec::new();
+        let init = Self::run_compute(&mut compute, self.creation_input(1), &mut input_backwards_offsets);
+        if input_backwards_offsets.is_empty() {
+            return Err(NoInputs);
+        }
+        Ok(self.push_compute_edge(input_backwards_offsets, compute, init))
+    }
+
+    /// Add the edge of a computed value which already ran on creation, reading `input_backwards_offsets` and computing `init`.
+    /// It reruns `compute` and sets the value to the result.
+    fn push_compute_edge<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, input_backwards_offsets: Vec<usize>, mut compute: F, init: T) -> CRx<'c, T, A> {
         let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
             input_backwards_offsets.clear();
             let output = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
             unsafe { outputs.next().unwrap().set_dyn(output); }
             debug_assert!(outputs.next().is_none());
         });
-        self.0.push(RxDAGElem::Edge(self.new_box(compute_edge)));
+        self.push_crx_edge(compute_edge, init)
+    }
+
+    /// Add `compute_edge` and its single output, whose value is `init`
+    fn push_crx_edge<T: 'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&Rx<'c, A>>) + 'c>(&self, compute_edge: RxEdgeImpl<'c, F, A>, init: T) -> CRx<'c, T, A> {
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
 
         let index = self.next_index();
         let rx = RxImpl::new(init);
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx)));
+        CRx::new(RxRef::new(self, index))
+    }
+
+    /// Create a computed value ([CRx]) which is `T::default()` until the next recompute, instead of computing on creation.
+    ///
+    /// This avoids throwaway computes when building a graph before its inputs have meaningful values.
+    /// Since dependencies are discovered by computing, the first recompute always computes the value
+    /// (even if no inputs changed), and it only recomputes on input changes afterwards.
+    pub fn new_crx_uninit<T: Default + 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, mut compute: F) -> CRx<'c, T, A> {
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(Vec::new(), 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let output = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            unsafe { outputs.next().unwrap().set_dyn(output); }
+            debug_assert!(outputs.next().is_none());
+        }).uninit();
+        self.push_crx_edge(compute_edge, T::default())
+    }
+
+    /// Create a computed value ([CRx]) which only changes when the hash of the computed value changes.
+    ///
+    /// This is a cheaper alternative to comparing with [PartialEq] for large values.
+    /// The edge stores the hash of the last value, and if a recomputed value has the same hash it's discarded,
+    /// so dependents don't recompute. If two different values have the same hash (a collision, which is
+    /// very unlikely) the change is missed.
+    pub fn new_crx_hash_dedup<T: Hash + 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, mut compute: F) -> CRx<'c, T, A> {
+        let mut input_backwards_offsets = Vec::new();
+        let init = Self::run_compute(&mut compute, self.creation_input(1), &mut input_backwards_offsets);
+        let mut last_hash = hash_of(&init);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let output = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            let hash = hash_of(&output);
+            let output_rx = outputs.next().unwrap();
+            if hash != last_hash {
+                last_hash = hash;
+                unsafe { output_rx.set_dyn(output); }
+            }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_crx_edge(compute_edge, init)
+    }
+
+    /// Create a computed value ([CRx]) in this DAG which computes according to `mode`.
+    ///
+    /// Lazy values don't compute until they're read, so they're useful for rarely-read branches.
+    /// Before then, [CRx::try_get] returns `None`.
+    pub fn new_crx_with<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, mode: Laziness, mut compute: F) -> CRx<'c, T, A> {
+        if mode == Laziness::Eager {
+            return self.new_crx(compute);
+        }
+
+        let compute: LazyCompute<'c, T, A> = Rc::new(RefCell::new(move |input: RxInput<'_, 'c, A>, input_backwards_offsets: &mut Vec<usize>| {
+            // We may be computing inside another compute, so don't steal its reads
+            let outer_reads = input.post_read();
+            let output = Self::run_compute(&mut compute, input, input_backwards_offsets);
+            input.restore_reads(outer_reads);
+            output
+        }));
+        let edge_compute = compute.clone();
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(Vec::new(), 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            let output = outputs.next().unwrap();
+            if mode == Laziness::OnDemand {
+                output.invalidate();
+            } else {
+                input_backwards_offsets.clear();
+                let value = (edge_compute.borrow_mut())(input, &mut input_backwards_offsets);
+                unsafe { output.set_dyn(value); }
+            }
+            debug_assert!(outputs.next().is_none());
+        }).lazy();
+        let edge_index = self.next_index();
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+
+        let index = self.next_index();
+        let rx = LazyRxImpl::new(edge_index, compute);
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx)));
+        CRx::new(RxRef::new(self, index))
+    }
+
+    /// Create a computed value ([CRx]) which only computes when it's read after its inputs changed.
+    ///
+    /// Recomputes only mark it out of date (so dependents still recompute), then the next read computes and caches it.
+    /// This is [RxDAG::new_crx_with] with [Laziness::OnDemand], useful for expensive values which are rarely read.
+    pub fn new_pull_crx<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, compute: F) -> CRx<'c, T, A> {
+        self.new_crx_with(Laziness::OnDemand, compute)
+    }
+
+    /// Create a computed value ([CRx]) which may alias an input instead of storing a copy.
+    ///
+    /// If `compute` returns [Cow::Borrowed] of an entire input value (e.g. `Cow::Borrowed(var.get(g))`),
+    /// the computed value points to that input, so reading it reads the input's value.
+    /// Otherwise, e.g. if the value is borrowed from *part* of an input or from outside the DAG, it's cloned.
+    /// [Cow::Owned] values are stored like in [RxDAG::new_crx].
+    ///
+    /// This is useful for pass-through computations like filters, which would otherwise clone large values.
+    pub fn new_crx_cow<T: Clone + 'c, F: for<'a> FnMut(RxInput<'a, 'c, A>) -> Cow<'a, T> + 'c>(&self, mut compute: F) -> CRx<'c, T, A> {
+        // Borrowed values escape as pointers, which are still valid after compute because nothing has changed
+        let mut compute = move |input: RxInput<'_, 'c, A>| match compute(input) {
+            Cow::Borrowed(value) => Err(value as *const T),
+            Cow::Owned(value) => Ok(value)
+        };
+        let mut input_backwards_offsets = Vec::new();
+        let input = self.creation_input(1);
+        let init = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+        let init = Self::resolve_cow(init, input, &input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let output = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            let output = Self::resolve_cow(output, input, input_backwards_offsets);
+            unsafe { outputs.next().unwrap().set_dyn(output); }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+
+        let index = self.next_index();
+        let rx = CowRxImpl::<'c, T, A>::new(init);
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx)));
+        CRx::new(RxRef::new(self, index))
+    }
+
+    /// Create a computed value ([CRx]) which is always the value of the node `select` returns, without copying it.
+    ///
+    /// Reading the computed value reads the selected node's value. The selected node is a dependency,
+    /// so `select` reruns when it changes (and when anything else `select` reads changes), and it can select a different node.
+    /// Unlike [RxDAG::new_crx_cow], this never clones, so `T` doesn't need to be [Clone].
+    pub fn new_alias_crx<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> RxRef<'c, T, A> + 'c>(&self, mut select: F) -> CRx<'c, T, A> {
+        let mut compute = move |input: RxInput<'_, 'c, A>| {
+            let selected = select(input);
+            // Reading makes the selected node an input, so this recomputes when it changes
+            selected.get(input);
+            CowValue::Alias(selected.raw().get_rx(input.0) as *const Rx<'c, A>)
+        };
+        let mut input_backwards_offsets = Vec::new();
+        let init = Self::run_compute(&mut compute, self.creation_input(1), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let output = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            unsafe { outputs.next().unwrap().set_dyn(output); }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+
+        let index = self.next_index();
+        let rx = CowRxImpl::<'c, T, A>::new(init);
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx)));
         CRx::new(RxRef::new(self, index))
     }
 
     /// Create 2 computed values ([CRx]s) in this DAG which are created from the same function.
     pub fn new_crx2<T1: 'c, T2: 'c, F: FnMut(RxInput<'_, 'c, A>) -> (T1, T2) + 'c>(&self, mut compute: F) -> (CRx<'c, T1, A>, CRx<'c, T2, A>) {
         let mut input_backwards_offsets = Vec::new();
-        let (init1, init2) = Self::run_compute(&mut compute, RxInput(self.sub_dag()), &mut input_backwards_offsets);
+        let (init1, init2) = Self::run_compute(&mut compute, self.creation_input(2), &mut input_backwards_offsets);
         let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 2, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
             input_backwards_offsets.clear();
             let (output1, output2) = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
@@ -164,20 +718,20 @@ impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
             unsafe { outputs.next().unwrap().set_dyn(output2); }
             debug_assert!(outputs.next().is_none());
         });
-        self.0.push(RxDAGElem::Edge(self.new_box(compute_edge)));
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
 
         let index = self.next_index();
         let rx1 = RxImpl::new(init1);
         let rx2 = RxImpl::new(init2);
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx1)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx2)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx1)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx2)));
         (CRx::new(RxRef::new(self, index)), CRx::new(RxRef::new(self, index + 1)))
     }
 
     /// Create 3 computed values ([CRx]s) in this DAG which are created from the same function.
     pub fn new_crx3<T1: 'c, T2: 'c, T3: 'c, F: FnMut(RxInput<'_, 'c, A>) -> (T1, T2, T3) + 'c>(&self, mut compute: F) -> (CRx<'c, T1, A>, CRx<'c, T2, A>, CRx<'c, T3, A>) {
         let mut input_backwards_offsets = Vec::new();
-        let (init1, init2, init3) = Self::run_compute(&mut compute, RxInput(self.sub_dag()), &mut input_backwards_offsets);
+        let (init1, init2, init3) = Self::run_compute(&mut compute, self.creation_input(3), &mut input_backwards_offsets);
         let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 3, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
             input_backwards_offsets.clear();
             let (output1, output2, output3) = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
@@ -186,22 +740,22 @@ impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
             unsafe { outputs.next().unwrap().set_dyn(output3); }
             debug_assert!(outputs.next().is_none());
         });
-        self.0.push(RxDAGElem::Edge(self.new_box(compute_edge)));
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
 
         let index = self.next_index();
         let rx1 = RxImpl::new(init1);
         let rx2 = RxImpl::new(init2);
         let rx3 = RxImpl::new(init3);
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx1)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx2)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx3)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx1)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx2)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx3)));
         (CRx::new(RxRef::new(self, index)), CRx::new(RxRef::new(self, index + 1)), CRx::new(RxRef::new(self, index + 2)))
     }
 
     /// Create 4 computed values ([CRx]s) in this DAG which are created from the same function.
     pub fn new_crx4<T1: 'c, T2: 'c, T3: 'c, T4: 'c, F: FnMut(RxInput<'_, 'c, A>) -> (T1, T2, T3, T4) + 'c>(&self, mut compute: F) -> (CRx<'c, T1, A>, CRx<'c, T2, A>, CRx<'c, T3, A>, CRx<'c, T4, A>) {
         let mut input_backwards_offsets = Vec::new();
-        let (init1, init2, init3, init4) = Self::run_compute(&mut compute, RxInput(self.sub_dag()), &mut input_backwards_offsets);
+        let (init1, init2, init3, init4) = Self::run_compute(&mut compute, self.creation_input(4), &mut input_backwards_offsets);
         let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 4, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
             input_backwards_offsets.clear();
             let (output1, output2, output3, output4) = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
@@ -211,24 +765,24 @@ impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
             unsafe { outputs.next().unwrap().set_dyn(output4); }
             debug_assert!(outputs.next().is_none());
         });
-        self.0.push(RxDAGElem::Edge(self.new_box(compute_edge)));
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
 
         let index = self.next_index();
         let rx1 = RxImpl::new(init1);
         let rx2 = RxImpl::new(init2);
         let rx3 = RxImpl::new(init3);
         let rx4 = RxImpl::new(init4);
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx1)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx2)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx3)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx4)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx1)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx2)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx3)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx4)));
         (CRx::new(RxRef::new(self, index)), CRx::new(RxRef::new(self, index + 1)), CRx::new(RxRef::new(self, index + 2)), CRx::new(RxRef::new(self, index + 3)))
     }
 
     /// Create 5 computed values ([CRx]s) in this DAG which are created from the same function.
     pub fn new_crx5<T1: 'c, T2: 'c, T3: 'c, T4: 'c, T5: 'c, F: FnMut(RxInput<'_, 'c, A>) -> (T1, T2, T3, T4, T5) + 'c>(&self, mut compute: F) -> (CRx<'c, T1, A>, CRx<'c, T2, A>, CRx<'c, T3, A>, CRx<'c, T4, A>, CRx<'c, T5, A>) {
         let mut input_backwards_offsets = Vec::new();
-        let (init1, init2, init3, init4, init5) = Self::run_compute(&mut compute, RxInput(self.sub_dag()), &mut input_backwards_offsets);
+        let (init1, init2, init3, init4, init5) = Self::run_compute(&mut compute, self.creation_input(5), &mut input_backwards_offsets);
         let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 5, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
             input_backwards_offsets.clear();
             let (output1, output2, output3, output4, output5) = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
@@ -239,7 +793,7 @@ impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
             unsafe { outputs.next().unwrap().set_dyn(output5); }
             debug_assert!(outputs.next().is_none());
         });
-        self.0.push(RxDAGElem::Edge(self.new_box(compute_edge)));
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
 
         let index = self.next_index();
         let rx1 = RxImpl::new(init1);
@@ -247,22 +801,320 @@ impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
         let rx3 = RxImpl::new(init3);
         let rx4 = RxImpl::new(init4);
         let rx5 = RxImpl::new(init5);
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx1)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx2)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx3)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx4)));
-        self.0.push(RxDAGElem::<'c>::Node(self.new_box(rx5)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx1)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx2)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx3)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx4)));
+        self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx5)));
         (CRx::new(RxRef::new(self, index)), CRx::new(RxRef::new(self, index + 1)), CRx::new(RxRef::new(self, index + 2)), CRx::new(RxRef::new(self, index + 3)), CRx::new(RxRef::new(self, index + 4)))
     }
     // endregion
+
+    /// Create `N` computed values ([CRx]s) of the same type in this DAG which are created from the same function.
+    pub fn new_crx_array<T: 'c, const N: usize, F: FnMut(RxInput<'_, 'c, A>) -> [T; N] + 'c>(&self, mut compute: F) -> [CRx<'c, T, A>; N] {
+        let mut input_backwards_offsets = Vec::new();
+        let inits = Self::run_compute(&mut compute, self.creation_input(N), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, N, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let outputs_array = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            for output in outputs_array {
+                unsafe { outputs.next().unwrap().set_dyn(output); }
+            }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+
+        let index = self.next_index();
+        for init in inits {
+            let rx = RxImpl::new(init);
+            self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx)));
+        }
+        std::array::from_fn(|offset| CRx::new(RxRef::new(self, index + offset)))
+    }
+
+    /// Create a computed value ([CRx]) which accumulates the elements of `source` incrementally.
+    ///
+    /// Starts with `init` and calls `add` for every element. When `source` changes, the new elements
+    /// are compared to the previous ones by position: `remove` is called for every old element which
+    /// changed or was removed, and `add` for every new element which changed or was added.
+    /// So pushing or popping an element is one call instead of recomputing from scratch.
+    ///
+    /// Keeps a clone of the previous `source` to compare against.
+    pub fn new_incremental_crx<T: Clone + PartialEq + 'c, Acc: Clone + 'c>(
+        &self,
+        source: Var<'c, Vec<T>, A>,
+        init: Acc,
+        add: impl Fn(&mut Acc, &T) + 'c,
+        remove: impl Fn(&mut Acc, &T) + 'c
+    ) -> CRx<'c, Acc, A> {
+        let mut acc = init;
+        let mut previous = Vec::new();
+        self.new_crx(move |g| {
+            let next = source.get(g);
+            for (index, old_elem) in previous.iter().enumerate() {
+                if next.get(index) != Some(old_elem) {
+                    remove(&mut acc, old_elem);
+                }
+            }
+            for (index, new_elem) in next.iter().enumerate() {
+                if previous.get(index) != Some(new_elem) {
+                    add(&mut acc, new_elem);
+                }
+            }
+            previous.clone_from(next);
+            acc.clone()
+        })
+    }
+
+    /// Create a group of computed values ([CRxGroup]) which are computed together.
+    ///
+    /// The number of computed values is the length of the vector returned by the initial computation,
+    /// and `compute` must return a vector with the same length every time.
+    pub fn new_crx_vec<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> Vec<T> + 'c>(&self, mut compute: F) -> CRxGroup<'c, T, A> {
+        let mut input_backwards_offsets = Vec::new();
+        // The number of outputs isn't known until it returns, so assume there are some
+        let inits = Self::run_compute(&mut compute, self.creation_input(1), &mut input_backwards_offsets);
+        let num_outputs = inits.len();
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, num_outputs, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let outputs_vec = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            assert_eq!(outputs_vec.len(), num_outputs, "new_crx_vec computation returned a different number of values");
+            for output in outputs_vec {
+                unsafe { outputs.next().unwrap().set_dyn(output); }
+            }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_elem(RxDAGElem::Edge(self.new_box(compute_edge)));
+
+        let index = self.next_index();
+        for init in inits {
+            let rx = RxImpl::new(init);
+            self.push_elem(RxDAGElem::<'c>::Node(self.new_box(rx)));
+        }
+        CRxGroup::new((0..num_outputs).map(|offset| CRx::new(RxRef::new(self, index + offset))).collect())
+    }
+
+    /// Create a computed value ([CRx]) which reads nodes chosen while computing, e.g. cells referenced by a spreadsheet formula.
+    ///
+    /// `compute` gets a function to read nodes of type `T2` by [UntypedRxRef] (see [RxInput::read_untyped]).
+    /// Every computation rediscovers its dependencies, so it recomputes when whichever nodes it last read change.
+    ///
+    /// # Safety
+    /// Every node `compute` reads must have type `T2`.
+    pub unsafe fn new_dynamic_crx<T2: 'c, T: 'c>(&self, mut compute: impl for<'a> FnMut(RxInput<'a, 'c, A>, &dyn Fn(UntypedRxRef<'c, A>) -> &'a T2) -> T + 'c) -> CRx<'c, T, A> {
+        self.new_crx(move |g| compute(g, &|rx| g.read_untyped(rx)))
+    }
+
+    /// Create a computed value ([CRx]) from the last `window` values of `source` (including the current one),
+    /// oldest first, e.g. for a moving average. Until `source` has changed `window - 1` times, there are fewer values.
+    pub fn new_windowed_crx<T: Clone + 'c, U: 'c>(&self, source: Var<'c, T, A>, window: usize, mut compute: impl FnMut(&VecDeque<T>) -> U + 'c) -> CRx<'c, U, A> {
+        assert!(window > 0, "RxDAG::new_windowed_crx: window must be positive");
+        let mut values = VecDeque::with_capacity(window);
+        let mut seen_changes = None;
+        self.new_crx(move |g| {
+            let value = source.get(g);
+            // Otherwise we're rerunning without a change (e.g. rediscovering dependencies), so the value is already in the window
+            let num_changes = source.raw().num_changes(g.0);
+            if seen_changes.replace(num_changes) != Some(num_changes) {
+                if values.len() == window {
+                    values.pop_front();
+                }
+                values.push_back(value.clone());
+            }
+            compute(&values)
+        })
+    }
+
+    /// Create a computed value ([CRx]) which folds every value of `source` into an accumulator with `step`,
+    /// e.g. a running total. It starts as `init` stepped with `source`'s value on creation.
+    ///
+    /// While `reset` is `true` the accumulator is `init` and `source`'s changes are ignored.
+    /// Once `reset` is `false` again, it accumulates from `source`'s next change.
+    pub fn new_accumulating_crx<T: 'c, Acc: Clone + 'c>(
+        &self,
+        source: CRx<'c, T, A>,
+        init: Acc,
+        mut step: impl FnMut(&mut Acc, &T) + 'c,
+        reset: Var<'c, bool, A>
+    ) -> CRx<'c, Acc, A> {
+        let mut acc = init.clone();
+        let mut seen_changes = None;
+        self.new_crx(move |g| {
+            let value = source.get(g);
+            // Otherwise we only recomputed because `reset` changed
+            let num_changes = source.raw().num_changes(g.0);
+            let did_change = seen_changes.replace(num_changes) != Some(num_changes);
+            if *reset.get(g) {
+                acc = init.clone();
+            } else if did_change {
+                step(&mut acc, value);
+            }
+            acc.clone()
+        })
+    }
+
+    /// Create a computed value ([CRx]) which is `source`'s value from the previous recompute (a one-recompute delay).
+    /// It starts as `source`'s value on creation.
+    ///
+    /// This recomputes when `source` changes, and on the recompute after that to catch up.
+    pub fn new_delayed_crx<T: Clone + 'c>(&self, source: CRx<'c, T, A>) -> CRx<'c, T, A> {
+        // Set when source changes so that we recompute (and catch up) on the next recompute
+        let catch_up = self.new_var(());
+        let mut held = None;
+        let mut seen_changes = None;
+        self.new_crx(move |g| {
+            catch_up.get(g);
+            let value = source.get(g);
+            let num_changes = source.raw().num_changes(g.0);
+            if seen_changes.replace(num_changes).is_some_and(|seen_changes| seen_changes != num_changes) {
+                // Also marks the DAG dirty, since it must recompute again
+                catch_up.raw().set_in(g.0, ());
+            }
+            held.replace(value.clone()).unwrap_or_else(|| value.clone())
+        })
+    }
+
+    /// Create a computed value ([CRx]) which is the sum of `inputs`. If `inputs` is empty it's the sum of nothing (e.g. 0).
+    pub fn sum_of<T: Clone + Sum + 'c>(&self, inputs: &[CRx<'c, T, A>]) -> CRx<'c, T, A> {
+        let inputs = inputs.to_vec();
+        self.new_crx(move |g| inputs.iter().map(|input| input.get(g).clone()).sum())
+    }
+
+    /// Create a computed value ([CRx]) which is the maximum of `inputs`, or `None` if `inputs` is empty.
+    pub fn max_of<T: Clone + Ord + 'c>(&self, inputs: &[CRx<'c, T, A>]) -> CRx<'c, Option<T>, A> {
+        let inputs = inputs.to_vec();
+        self.new_crx(move |g| inputs.iter().map(|input| input.get(g)).max().cloned())
+    }
+
+    /// Create a computed value ([CRx]) which is the minimum of `inputs`, or `None` if `inputs` is empty.
+    pub fn min_of<T: Clone + Ord + 'c>(&self, inputs: &[CRx<'c, T, A>]) -> CRx<'c, Option<T>, A> {
+        let inputs = inputs.to_vec();
+        self.new_crx(move |g| inputs.iter().map(|input| input.get(g)).min().cloned())
+    }
+
+    /// Create a computed value ([CRx]) from 2 inputs. The inputs are passed by reference, so they don't need to be [Clone].
+    pub fn map2<T1: 'c, T2: 'c, T: 'c>(&self, a: impl Readable<'c, T1, A> + 'c, b: impl Readable<'c, T2, A> + 'c, f: impl Fn(&T1, &T2) -> T + 'c) -> CRx<'c, T, A> {
+        self.new_crx(move |g| f(a.get(g), b.get(g)))
+    }
+
+    /// Create a computed value ([CRx]) from 3 inputs. See [RxDAG::map2].
+    pub fn map3<T1: 'c, T2: 'c, T3: 'c, T: 'c>(&self, a: impl Readable<'c, T1, A> + 'c, b: impl Readable<'c, T2, A> + 'c, c: impl Readable<'c, T3, A> + 'c, f: impl Fn(&T1, &T2, &T3) -> T + 'c) -> CRx<'c, T, A> {
+        self.new_crx(move |g| f(a.get(g), b.get(g), c.get(g)))
+    }
+
+    /// Create a computed value ([CRx]) from 4 inputs. See [RxDAG::map2].
+    pub fn map4<T1: 'c, T2: 'c, T3: 'c, T4: 'c, T: 'c>(&self, a: impl Readable<'c, T1, A> + 'c, b: impl Readable<'c, T2, A> + 'c, c: impl Readable<'c, T3, A> + 'c, d: impl Readable<'c, T4, A> + 'c, f: impl Fn(&T1, &T2, &T3, &T4) -> T + 'c) -> CRx<'c, T, A> {
+        self.new_crx(move |g| f(a.get(g), b.get(g), c.get(g), d.get(g)))
+    }
+
+    /// Create a computed value ([CRx]) which is the element of `sources` selected by `index`.
+    ///
+    /// Only the selected element is a dependency, so changes to the others don't recompute this.
+    /// Like slice indexing, if `index` is out of bounds the computation panics (see [RxDAG::recompute_catching]).
+    /// Use [RxDAG::try_index_into] to get `None` instead.
+    pub fn index_into<T: Clone + 'c>(&self, sources: Vec<CRx<'c, T, A>>, index: Var<'c, usize, A>) -> CRx<'c, T, A> {
+        self.new_crx(move |g| {
+            let index = *index.get(g);
+            match sources.get(index) {
+                None => panic!("RxDAG::index_into: index {} out of bounds for {} sources", index, sources.len()),
+                Some(source) => source.get(g).clone()
+            }
+        })
+    }
+
+    /// Create a computed value ([CRx]) which is the element of `sources` selected by `index`,
+    /// or `None` if `index` is out of bounds. See [RxDAG::index_into].
+    pub fn try_index_into<T: Clone + 'c>(&self, sources: Vec<CRx<'c, T, A>>, index: Var<'c, usize, A>) -> CRx<'c, Option<T>, A> {
+        self.new_crx(move |g| sources.get(*index.get(g)).map(|source| source.get(g).clone()))
+    }
+
+    /// Create a computed value ([CRx]) which, when inputs change, only recomputes if `should_recompute` returns `true`.
+    ///
+    /// `should_recompute` is given which inputs changed. If it returns `false`, the value is retained
+    /// and dependents don't recompute. This generalizes [RxDAG::new_gated_crx] and can be used for throttling.
+    pub fn new_crx_when<T: 'c, P: Fn(&ChangedInputs) -> bool + 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, should_recompute: P, mut compute: F) -> CRx<'c, T, A> {
+        let mut input_backwards_offsets = Vec::new();
+        let init = Self::run_compute(&mut compute, self.creation_input(1), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            let output = Self::run_compute(&mut compute, input, &mut input_backwards_offsets);
+            unsafe { outputs.next().unwrap().set_dyn(output); }
+            debug_assert!(outputs.next().is_none());
+        }).with_should_recompute(should_recompute);
+        self.push_crx_edge(compute_edge, init)
+    }
+
+    /// Create a chain of computed values ([CRx]s), where each stage can read the outputs of the stages before it.
+    ///
+    /// Add stages with [CRxPipeline::stage], then get every stage's output with [CRxPipeline::build].
+    pub fn new_crx_pipeline<T: 'c>(&self) -> CRxPipeline<'_, 'c, T, A> {
+        CRxPipeline {
+            graph: self,
+            outputs: Vec::new()
+        }
+    }
+
+    /// Create a computed value ([CRx]) which only recomputes while `gate` is `true`.
+    ///
+    /// The value is always computed once on creation. Afterwards, while `gate` is `false`,
+    /// `compute` isn't called and the value is retained even if other inputs change,
+    /// so dependents don't recompute either.
+    ///
+    /// Note that input changes while the gate is closed are "missed": they are only reflected
+    /// once `gate` becomes `true` again, at which point the value is recomputed with the current inputs.
+    pub fn new_gated_crx<T: 'c, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(&self, gate: Var<'c, bool, A>, mut compute: F) -> CRx<'c, T, A> {
+        let mut input_backwards_offsets = Vec::new();
+        let init = Self::run_compute(&mut |input| {
+            gate.get(input);
+            compute(input)
+        }, self.creation_input(1), &mut input_backwards_offsets);
+        let compute_edge = RxEdgeImpl::<'c, _, A>::new(input_backwards_offsets, 1, move |mut input_backwards_offsets: &mut Vec<usize>, input: RxInput<'_, 'c, A>, outputs: &mut dyn Iterator<Item=&Rx<'c, A>>| {
+            input_backwards_offsets.clear();
+            // If the gate is closed we only read (and therefore only depend on) the gate
+            let output = Self::run_compute(&mut |input| {
+                if *gate.get(input) {
+                    Some(compute(input))
+                } else {
+                    None
+                }
+            }, input, &mut input_backwards_offsets);
+            let output_rx = outputs.next().unwrap();
+            if let Some(output) = output {
+                unsafe { output_rx.set_dyn(output); }
+            }
+            debug_assert!(outputs.next().is_none());
+        });
+        self.push_crx_edge(compute_edge, init)
+    }
 }
 
 impl<'c, A: Allocator> RxDAG<'c, A> {
     fn next_index(&self) -> usize {
-        self.0.len()
+        self.elems.len()
+    }
+
+    /// Check that `additional` more elements can be added without exceeding [RxDAG::with_max_elements]
+    fn check_capacity(&self, additional: usize) -> Result<(), CapacityExceeded> {
+        match self.max_elements {
+            Some(max) if self.elems.len() + additional > max => Err(CapacityExceeded { max }),
+            _ => Ok(())
+        }
     }
 
-    fn run_compute<T, F: FnMut(RxInput<'_, 'c, A>) -> T + 'c>(compute: &mut F, input: RxInput<'_, 'c, A>, input_backwards_offsets: &mut Vec<usize>) -> T {
+    fn push_elem(&self, elem: RxDAGElem<'c, A>) {
+        // Edges reserve space for their outputs, so we never add an edge without them
+        let additional = match &elem {
+            RxDAGElem::Node(_) => 1,
+            RxDAGElem::Edge(edge) => 1 + edge.num_outputs()
+        };
+        if let Err(error) = self.check_capacity(additional) {
+            panic!("{}", error);
+        }
+        self.elems.push(elem);
+        self.read_flags.grow(self.elems.len());
+    }
+
+    fn run_compute<T, F: FnMut(RxInput<'_, 'c, A>) -> T>(compute: &mut F, input: RxInput<'_, 'c, A>, input_backwards_offsets: &mut Vec<usize>) -> T {
         debug_assert!(input_backwards_offsets.is_empty());
 
         let result = compute(input);
@@ -275,49 +1127,620 @@ impl<'c, A: Allocator> RxDAG<'c, A> {
         result
     }
 
+    /// Alias the value if it's an entire input of the same type, otherwise clone it.
+    fn resolve_cow<T: Clone>(value: Result<T, *const T>, input: RxInput<'_, 'c, A>, input_backwards_offsets: &[usize]) -> CowValue<'c, T, A> {
+        match value {
+            Ok(value) => CowValue::Owned(value),
+            Err(ptr) => match Self::find_aliased::<T>(ptr, input, input_backwards_offsets) {
+                Some(node) => CowValue::Alias(node),
+                None => CowValue::Owned(unsafe { &*ptr }.clone())
+            }
+        }
+    }
+
+    /// The input whose value is at `ptr`, if any
+    fn find_aliased<T>(ptr: *const T, input: RxInput<'_, 'c, A>, input_backwards_offsets: &[usize]) -> Option<*const Rx<'c, A>> {
+        input_backwards_offsets.iter().find_map(|offset| {
+            let node = unsafe { input.0.before.get_unchecked(input.0.index - offset) }.as_node().expect("broken RxDAG: RxEdge input must be a node");
+            let node_ptr = unsafe { node._value_ptr_dyn() };
+            (node_ptr == ptr as *const () && node.value_type_name() == type_name::<T>()).then_some(node as *const Rx<'c, A>)
+        })
+    }
+
     /// Update all [Var]s with their new values and recompute [CRx]s.
     ///
     /// This requires a shared reference and actually does the "reactive updates".
     pub fn recompute(&mut self) {
-        for (index, (before, current, after)) in self.0.as_mut().iter_mut_split3s().enumerate() {
-            current.recompute(index, before, after, self.1);
+        self.begin_recompute();
+        for (index, (before, current, after)) in self.elems.as_mut().iter_mut_split3s().enumerate() {
+            if current.recompute(index, before, after, self.id, self.mismatch_policy, &self.dirty, &self.read_flags) {
+                self.recomputed.push(index);
+            }
+        }
+        self.finish_recompute();
+    }
+
+    /// Update all values like [RxDAG::recompute], but only run side-effects tagged with `tag` (see [RxDAG::run_crx_tagged]).
+    ///
+    /// Other side-effects whose inputs changed, including untagged ones, are postponed until the next recompute
+    /// which runs them. Computed values are always updated, and side-effects which need the old values of their inputs
+    /// (e.g. [CRx::on_transition]) always run, since the old values are gone by the next recompute.
+    pub fn recompute_tag(&mut self, tag: u32) {
+        self.begin_recompute();
+        for (index, (before, current, after)) in self.elems.as_mut().iter_mut_split3s().enumerate() {
+            if let Some(edge) = current.as_edge_mut() {
+                if edge.num_outputs() == 0 && edge.tag() != Some(tag) && edge.can_postpone() {
+                    edge.postpone(index, before);
+                    continue;
+                }
+            }
+            if current.recompute(index, before, after, self.id, self.mismatch_policy, &self.dirty, &self.read_flags) {
+                self.recomputed.push(index);
+            }
+        }
+        self.finish_recompute();
+    }
+
+    /// Recompute only what `crx` transitively depends on, and return its value.
+    ///
+    /// Everything else stays stale until the next recompute, which updates it as usual.
+    /// This is based on the current dependencies: if `crx` reads new inputs when it recomputes, they may be stale.
+    ///
+    /// Side-effects are postponed until the next recompute (except those which need the old values of their inputs,
+    /// like in [RxDAG::recompute_tag]). Otherwise this is a regular recompute:
+    /// it counts as a revision, and [RxDAG::after_next_recompute] callbacks run at the end.
+    pub fn evaluate<T: Clone>(&mut self, crx: CRx<'c, T, A>) -> T where A: 'c {
+        if crx.raw().raw().graph_id() != self.id {
+            self.mismatch_policy.on_unskippable_mismatch("RxDAG::evaluate");
+        }
+        let is_needed = self.transitive_inputs_mask(crx.raw().raw().index());
+        self.begin_recompute();
+        // Other values may still need to recompute
+        *self.dirty.get_mut() = true;
+        for (index, (before, current, after)) in self.elems.as_mut().iter_mut_split3s().enumerate() {
+            // Side-effects which can't be postponed only run if their inputs changed, so they're fine to recompute
+            let can_postpone = current.as_edge_mut().is_none_or(|edge| edge.can_postpone());
+            if is_needed.get(index).copied().unwrap_or(false) || !can_postpone {
+                if current.recompute(index, before, after, self.id, self.mismatch_policy, &self.dirty, &self.read_flags) {
+                    self.recomputed.push(index);
+                }
+            } else if let Some(edge) = current.as_edge_mut() {
+                edge.postpone(index, before);
+            }
+        }
+        self.finish_recompute();
+        crx.get(self.stale()).clone()
+    }
+
+    /// Recompute only if at least `min_interval` passed since this last recomputed, and return whether it did.
+    ///
+    /// Call this in a loop which receives rapid events to coalesce their sets into fewer recomputes.
+    /// Only recomputes from this method (and [RxDAG::recompute_if_elapsed_at]) count.
+    pub fn recompute_if_elapsed(&mut self, min_interval: Duration) -> bool {
+        self.recompute_if_elapsed_at(min_interval, Instant::now())
+    }
+
+    /// [RxDAG::recompute_if_elapsed] where the current time is `now`, e.g. to use a different clock in tests.
+    pub fn recompute_if_elapsed_at(&mut self, min_interval: Duration, now: Instant) -> bool {
+        if let Some(last_recompute) = self.last_throttled_recompute {
+            if now.saturating_duration_since(last_recompute) < min_interval {
+                return false;
+            }
+        }
+        self.last_throttled_recompute = Some(now);
+        self.recompute();
+        true
+    }
+
+    /// Returns a [RecomputeStepper] which recomputes one element at a time, e.g. for debugging.
+    pub fn recompute_stepper(&mut self) -> RecomputeStepper<'_, 'c, A> {
+        self.begin_recompute();
+        RecomputeStepper::new(self)
+    }
+
+    pub(crate) fn begin_recompute(&mut self) {
+        self.apply_external_inputs();
+        self.revision += 1;
+        *self.dirty.get_mut() = false;
+        self.recomputed.clear();
+    }
+
+    /// Recompute the element at `index`. Elements must be recomputed in order.
+    pub(crate) fn recompute_elem(&mut self, index: usize) {
+        let (before, current, after) = self.elems.as_mut().split3_mut(index);
+        if current.recompute(index, before, after, self.id, self.mismatch_policy, &self.dirty, &self.read_flags) {
+            self.recomputed.push(index);
+        }
+    }
+
+    /// Reset the flags of recomputed nodes and run deferred edges
+    pub(crate) fn finish_recompute(&mut self) {
+        self.record_volatility();
+        self.record_epoch();
+        self.record_history();
+        self.record_changes();
+        let elems = self.elems.as_mut();
+        for index in self.recomputed.iter().copied() {
+            elems[index].post_recompute();
+        }
+
+        for index in self.recomputed.iter().copied() {
+            let (before, current, _after) = elems.split3_mut(index);
+            current.run_deferred(index, before, self.id, self.mismatch_policy, &self.dirty, &self.read_flags);
+        }
+        self.run_after_recompute();
+    }
+
+    /// Record the nodes which changed in the current recompute
+    fn record_changes(&mut self) {
+        // Elements created since the last recompute were created in the last revision
+        self.last_changes.resize(self.elems.len(), self.revision - 1);
+        for index in self.recomputed.iter().copied() {
+            self.last_changes[index] = self.revision;
+        }
+    }
+
+    /// Revision the node last changed (see [RxDAG::revision]), or when it was created if it hasn't changed since
+    pub(crate) fn last_change(&self, index: usize) -> u64 {
+        self.last_changes.get(index).copied().unwrap_or(self.revision)
+    }
+
+    /// Run a callback once at the end of the next recompute, after all nodes and side-effects are updated.
+    ///
+    /// Unlike [RxDAG::run_crx], this doesn't depend on any values: it always runs, but only once.
+    pub fn after_next_recompute(&self, f: impl FnOnce() + 'c) {
+        self.after_recompute.borrow_mut().push(Box::new(f));
+    }
+
+    fn run_after_recompute(&self) {
+        let callbacks = self.after_recompute.take();
+        for callback in callbacks {
+            callback();
+        }
+    }
+
+    /// Like [RxDAG::recompute], but if a computation panics, catches it and returns [EdgePanic].
+    ///
+    /// When an edge panics, the graph is partially updated: nodes before it have their new values,
+    /// but nodes after it still have their values from the previous recompute, even if they're computed from nodes before it.
+    /// Values set on nodes after it (e.g. [Var]s after it) stay pending, so the next recompute applies them,
+    /// unless you call [RxDAG::discard_pending] first.
+    /// The panicking edge reruns on the next recompute even if its inputs don't change,
+    /// and edges after it rerun if their inputs changed before the panic, so that recompute finishes updating the graph.
+    ///
+    /// Computations aren't required to be [std::panic::UnwindSafe]: they are wrapped in [AssertUnwindSafe],
+    /// so if they panic, any state they capture may be partially modified.
+    pub fn recompute_catching(&mut self) -> Result<(), EdgePanic> {
+        self.begin_recompute();
+        let mut panic = None;
+        for (index, (before, current, after)) in self.elems.as_mut().iter_mut_split3s().enumerate() {
+            let (id, mismatch_policy, dirty, read_flags) = (self.id, self.mismatch_policy, &self.dirty, &self.read_flags);
+            match catch_unwind(AssertUnwindSafe(|| current.recompute(index, before, after, id, mismatch_policy, dirty, read_flags))) {
+                Ok(false) => {}
+                Ok(true) => self.recomputed.push(index),
+                Err(payload) => {
+                    panic = Some(EdgePanic { index, payload });
+                    break;
+                }
+            }
+        }
+        self.record_volatility();
+        self.record_epoch();
+        self.record_history();
+        self.record_changes();
+
+        let elems = self.elems.as_mut();
+        if let Some(panic) = panic {
+            // Edges after the panic didn't run, so they must run on the next recompute if their inputs changed
+            for (index, (before, current, _after)) in elems.iter_mut_split3s().enumerate().skip(panic.index + 1) {
+                if let Some(edge) = current.as_edge_mut() {
+                    edge.postpone(index, before);
+                }
+            }
+            // Nodes before the edge are committed, but may have read flags from the interrupted computation
+            self.read_flags.clear();
+            for index in self.recomputed.iter().copied() {
+                elems[index].post_recompute();
+            }
+            // Nodes after the edge still have pending values
+            *self.dirty.get_mut() = true;
+            return Err(panic);
+        }
+
+        for index in self.recomputed.iter().copied() {
+            elems[index].post_recompute();
         }
 
-        for current in self.0.as_mut().iter_mut() {
-            current.post_recompute();
+        for index in self.recomputed.iter().copied() {
+            let (before, current, _after) = elems.split3_mut(index);
+            let (id, mismatch_policy, dirty, read_flags) = (self.id, self.mismatch_policy, &self.dirty, &self.read_flags);
+            if let Err(payload) = catch_unwind(AssertUnwindSafe(|| current.run_deferred(index, before, id, mismatch_policy, dirty, read_flags))) {
+                self.read_flags.clear_before(index);
+                return Err(EdgePanic { index, payload });
+            }
         }
+        self.run_after_recompute();
+        Ok(())
     }
 
     /// Recomputes if necessary and then returns an [RxContext] you can use to get the current value.
+    ///
+    /// If the DAG is [suspended](RxDAG::suspend) this doesn't recompute, so it's like [RxDAG::stale].
     pub fn now(&mut self) -> RxDAGSnapshot<'_, 'c, A> {
+        if !self.suspended {
+            self.recompute();
+        }
+        RxDAGSnapshot(self)
+    }
+
+    /// Recompute once and run `f` with an [RxContext] to read the new values. See [with_snapshot].
+    ///
+    /// Unlike [RxDAG::now], references from the snapshot can't escape `f`.
+    pub fn with_snapshot<R>(&mut self, f: impl for<'a> FnOnce(RxDAGSnapshot<'a, 'c, A>) -> R) -> R where A: 'c {
+        f(self.now())
+    }
+
+    /// Set the variable, recompute, and return an [RxContext] to read the new values.
+    pub fn set_and_now<T>(&mut self, var: Var<'c, T, A>, value: T) -> RxDAGSnapshot<'_, 'c, A> where A: 'c {
+        var.set(&*self, value);
         self.recompute();
         RxDAGSnapshot(self)
     }
 
+    /// Stop [RxDAG::now] from recomputing until [RxDAG::resume_and_recompute], so sets accumulate
+    /// (e.g. during a frame) and are all applied in one recompute.
+    ///
+    /// Explicitly calling [RxDAG::recompute] still recomputes.
+    pub fn suspend(&mut self) {
+        self.suspended = true;
+    }
+
+    /// Undo [RxDAG::suspend] and recompute, applying every value set while suspended.
+    pub fn resume_and_recompute(&mut self) {
+        self.suspended = false;
+        self.recompute();
+    }
+
+    /// Whether the DAG is [suspended](RxDAG::suspend).
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    /// Get a mutable reference to the variable's latest value, to mutate it directly instead of via [Var::set] or [Var::modify].
+    ///
+    /// This is sound because `&mut self` ensures there are no other references to any values.
+    /// The variable is marked changed, so dependents will recompute on the next [RxDAG::recompute]
+    /// (even if the value is not actually changed).
+    pub fn get_mut<T>(&mut self, var: Var<'c, T, A>) -> &mut T {
+        let var = var.raw().raw();
+        if var.graph_id() != self.id {
+            self.mismatch_policy.on_unskippable_mismatch("RxDAG::get_mut");
+        }
+        let elem = &mut self.elems.as_mut()[var.index()];
+        let rx = elem.as_node_mut().expect("RxRef is corrupt: it points to an edge");
+        *self.dirty.get_mut() = true;
+        unsafe { rx.get_mut_dyn() }
+    }
+
+    /// Rerun the computation of `crx` to rediscover which values it reads, e.g. if it reads different values
+    /// depending on non-reactive state which changed. Does nothing if `crx` isn't computed.
+    ///
+    /// The computation sets its outputs, which are committed on the next [RxDAG::recompute].
+    /// So if the computation has its own state, it must handle rerunning when its inputs didn't change
+    /// (the built-in stateful computations, e.g. [RxDAG::new_windowed_crx] and [RxDAG::new_delayed_crx], do).
+    /// Otherwise the rerun is observable, like any other extra run.
+    pub fn rediscover_deps(&mut self, crx: UntypedRxRef<'c, A>) {
+        if !self.check_graph(crx.graph_id(), "RxDAG::rediscover_deps") {
+            return;
+        }
+        if let Some(edge_index) = self.producing_edge_index(crx.index()) {
+            let (before, current, after) = self.elems.as_mut().split3_mut(edge_index);
+            let edge = current.as_edge_mut().expect("producing_edge_index returned a node");
+            edge.rediscover(edge_index, before, after, self.id, self.mismatch_policy, &self.dirty, &self.read_flags);
+            *self.dirty.get_mut() = true;
+        }
+    }
+
+    /// Temporarily override `var` with `value` while running `f`, e.g. for testing.
+    ///
+    /// This recomputes, saves the variable's value, sets it to `value`, recomputes, and runs `f`.
+    /// Afterwards it restores the saved value (even if `f` set the variable) and recomputes again.
+    pub fn with_var_override<T: Clone, R>(&mut self, var: Var<'c, T, A>, value: T, f: impl FnOnce(&mut Self) -> R) -> R where A: 'c {
+        let original = var.get(self.now()).clone();
+        var.set(&*self, value);
+        self.recompute();
+        let result = f(self);
+        var.set(&*self, original);
+        self.recompute();
+        result
+    }
+
     /// Returns an [RxContext] you can use to get the current value.
     /// However any newly-set values or computations will not be returned until [RxDAG::recompute] is called.
     pub fn stale(&self) -> RxDAGSnapshot<'_, 'c, A> {
         RxDAGSnapshot(self)
     }
 
+    /// Set what happens when a ref from a different graph is used to access this graph,
+    /// including in computations. By default this is only checked in debug builds.
+    pub fn set_mismatch_policy(&mut self, policy: MismatchPolicy) {
+        self.mismatch_policy = policy;
+    }
+
+    /// Handle `graph_id` not being this graph's according to the [MismatchPolicy], e.g. when `context` is given a ref.
+    /// Returns whether to go ahead, or `false` if `context` should be skipped (see [MismatchPolicy::Log]).
+    pub(crate) fn check_graph(&self, graph_id: RxDAGUid<'c, A>, context: &str) -> bool {
+        graph_id == self.id || self.mismatch_policy.on_mismatch(context)
+    }
+
+    /// Set the maximum number of elements (see [RxDAG::with_max_elements]), or `None` for no maximum.
+    pub fn set_max_elements(&mut self, max: Option<usize>) {
+        self.max_elements = max;
+    }
+
+    /// How many times the DAG has recomputed. Each recompute commits a new revision of values.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Whether the node changed (was set or recomputed) in the last [RxDAG::recompute].
+    pub fn did_recompute(&self, rx: UntypedRxRef<'c, A>) -> bool {
+        self.check_graph(rx.graph_id(), "RxDAG::did_recompute") && self.recomputed.contains(&rx.index())
+    }
+
+    /// Forget which nodes changed in the last [RxDAG::recompute] without recomputing,
+    /// so [RxDAG::did_recompute] returns `false` for every node until the next recompute.
+    ///
+    /// Values and dependencies are unaffected: the next recompute still updates everything set since the last one.
+    pub fn clear_changed_flags(&mut self) {
+        self.recomputed.clear();
+    }
+
+    /// Panics unless the node changed in the last [RxDAG::recompute]. For tests.
+    #[track_caller]
+    pub fn assert_recomputed(&self, rx: UntypedRxRef<'c, A>) {
+        assert!(self.did_recompute(rx), "expected node {} to recompute in revision {}, but it didn't", rx.index(), self.revision);
+    }
+
+    /// Panics if the node changed in the last [RxDAG::recompute]. For tests.
+    #[track_caller]
+    pub fn assert_not_recomputed(&self, rx: UntypedRxRef<'c, A>) {
+        assert!(!self.did_recompute(rx), "expected node {} to not recompute in revision {}, but it did", rx.index(), self.revision);
+    }
+
+    /// Panics if the DAG [RxDAG::is_dirty] or [RxDAG::would_recompute], i.e. something was set and not recomputed.
+    /// For event loops and tests, to catch reading from [RxDAG::stale] before recomputing.
+    #[track_caller]
+    pub fn assert_settled(&self) {
+        assert!(!self.is_dirty(), "expected RxDAG to be settled in revision {}, but a variable was set", self.revision);
+        if let Some(index) = self.elems.iter().position(|elem| elem.as_node().is_some_and(|node| node.has_pending())) {
+            panic!("expected RxDAG to be settled in revision {}, but node {} has a pending value", self.revision, index);
+        }
+    }
+
+    /// Run `f` with a view where every read is from the current revision.
+    ///
+    /// This is already guaranteed by the view borrowing the DAG (so it can't recompute),
+    /// but this makes it explicit, and the revision is checked in debug builds.
+    /// Like [RxDAG::stale], newly-set values will not be returned until [RxDAG::recompute] is called.
+    pub fn read_consistent<R>(&self, f: impl FnOnce(ConsistentView<'_, 'c, A>) -> R) -> R where A: 'c {
+        let revision = self.revision;
+        let result = f(ConsistentView {
+            snapshot: self.stale(),
+            revision
+        });
+        debug_assert_eq!(self.revision, revision, "RxDAG::read_consistent: recomputed during reads");
+        result
+    }
+
+    /// Read something from the DAG, then set something using the result.
+    ///
+    /// `read` returns an owned value, so its borrows end before `set`. Reads see the last recompute
+    /// (e.g. via [RxDAG::stale]); to modify a variable's latest value use [Var::modify] instead.
+    pub fn read_then_set<U>(&self, read: impl FnOnce(&Self) -> U, set: impl FnOnce(&Self, U)) {
+        let value = read(self);
+        set(self, value)
+    }
+
+    /// Whether a variable was set since the last [RxDAG::recompute], so [RxDAG::stale] may return outdated values.
+    ///
+    /// This includes variables set by computations (e.g. [RxDAG::link_vars]), which are applied on the next recompute.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// Whether [RxDAG::recompute] would change anything, i.e. a node has a pending value.
+    ///
+    /// Unlike [RxDAG::is_dirty] this checks every node, so it's slower, but it's only `true` if a node actually has
+    /// a pending value (e.g. not if a variable was set and then [Var::inspect_pending] removed the value).
+    pub fn would_recompute(&self) -> bool {
+        self.elems.iter().any(|elem| elem.as_node().is_some_and(|node| node.has_pending()))
+    }
+
+    /// Discard every value set since the last recompute, e.g. to roll back after [RxDAG::recompute_catching] fails.
+    ///
+    /// Mutations through [RxDAG::get_mut] already happened, so they aren't discarded.
+    /// If a recompute was interrupted, the next recompute still reruns the edges which didn't finish.
+    pub fn discard_pending(&mut self) {
+        for elem in self.elems.as_mut().iter_mut() {
+            if let Some(node) = elem.as_node_mut() {
+                node.discard_pending();
+            }
+        }
+    }
+
+    /// When enabled, reading from [RxDAG::stale] while the DAG is dirty logs a warning and increments [RxDAG::stale_reads].
+    ///
+    /// This is for debugging accidental uses of [RxDAG::stale] instead of [RxDAG::now].
+    pub fn warn_on_stale_read(&self, enabled: bool) {
+        self.warn_on_stale_read.set(enabled);
+    }
+
+    /// How many values were read while stale, when [RxDAG::warn_on_stale_read] was enabled.
+    pub fn stale_reads(&self) -> usize {
+        self.stale_reads.get()
+    }
+
+    /// The entire DAG, without checking stale reads (like [RxDAGSnapshot])
+    fn full_sub_dag(&self) -> RxSubDAG<'_, 'c, A> {
+        RxSubDAG {
+            before: FrozenSlice::from(&self.elems),
+            index: self.elems.len(),
+            id: self.id,
+            mismatch_policy: self.mismatch_policy,
+            num_outputs: 0,
+            dirty: &self.dirty,
+            read_flags: &self.read_flags,
+            stale_reads: None,
+            set_listeners: None
+        }
+    }
+
+    /// What a new computation with `num_outputs` outputs reads when it first runs, before its edge is added
+    fn creation_input(&self, num_outputs: usize) -> RxInput<'_, 'c, A> {
+        RxInput(RxSubDAG {
+            num_outputs,
+            ..self.full_sub_dag()
+        })
+    }
+
     pub(crate) fn id(&self) -> RxDAGUid<'c, A> {
-        self.1
+        self.id
+    }
+
+    pub(crate) fn elems(&self) -> &FrozenVec<RxDAGElem<'c, A>, A> {
+        &self.elems
+    }
+
+    pub(crate) fn volatility(&self) -> Option<&VolatilityTracker> {
+        self.volatility.as_ref()
+    }
+
+    pub(crate) fn volatility_mut(&mut self) -> &mut Option<VolatilityTracker> {
+        &mut self.volatility
+    }
+
+    pub(crate) fn epoch_mut(&mut self) -> &mut Option<Epoch> {
+        &mut self.epoch
+    }
+
+    pub(crate) fn dynamic_cloners(&self) -> &RefCell<HashMap<usize, DynamicEntry>> {
+        &self.dynamic_cloners
+    }
+
+    pub(crate) fn debug_formatters(&self) -> &RefCell<HashMap<usize, DebugEntry>> {
+        &self.debug_formatters
+    }
+
+    pub(crate) fn read_flags(&self) -> &FlagSet {
+        &self.read_flags
+    }
+
+    pub(crate) fn history(&self) -> &RefCell<HashMap<usize, NodeHistory>> {
+        &self.history
+    }
+
+    pub(crate) fn external_inputs(&self) -> &RefCell<Vec<ExternalInput<'c, A>>> {
+        &self.external_inputs
+    }
+
+    pub(crate) fn set_listeners(&self) -> &SetListeners<'c> {
+        &self.set_listeners
+    }
+
+    pub(crate) fn recomputed(&self) -> &[usize] {
+        &self.recomputed
+    }
+
+    pub(crate) fn elems_mut(&mut self) -> &mut [RxDAGElem<'c, A>] {
+        self.elems.as_mut()
+    }
+
+    pub(crate) fn collectable(&self) -> &RefCell<Vec<(usize, Weak<()>)>> {
+        &self.collectable
+    }
+}
+
+impl<'a, 'c: 'a, T: 'c, A: Allocator + Clone + 'c> CRxPipeline<'a, 'c, T, A> {
+    /// Add a stage, which is given its input and the outputs of all previous stages, in order.
+    pub fn stage<F: FnMut(RxInput<'_, 'c, A>, &[CRx<'c, T, A>]) -> T + 'c>(mut self, mut compute: F) -> Self {
+        let prev_outputs = self.outputs.clone();
+        let output = self.graph.new_crx(move |input| compute(input, &prev_outputs));
+        self.outputs.push(output);
+        self
+    }
+
+    /// Returns the outputs of every stage, in order.
+    pub fn build(self) -> Vec<CRx<'c, T, A>> {
+        self.outputs
+    }
+}
+
+impl<'a, 'c: 'a, A: Allocator> RxDAGSnapshot<'a, 'c, A> {
+    pub(crate) fn graph(self) -> &'a RxDAG<'c, A> {
+        self.0
+    }
+
+    /// Read a value. Equivalent to `rx.get(snapshot)`.
+    pub fn read<T>(self, rx: impl Readable<'c, T, A>) -> &'a T {
+        rx.get(self)
+    }
+
+    /// Read and clone each computed value, in order.
+    pub fn read_many<T: Clone + 'c>(self, refs: &[CRx<'c, T, A>]) -> Vec<T> {
+        refs.iter().map(|crx| crx.get(self).clone()).collect()
+    }
+
+    /// Returns a view of what an edge at `index` would see: only nodes before `index` can be read.
+    /// Nodes at or after `index` are out of range (see [RxRef::try_get]).
+    ///
+    /// If `index` is past the end of the DAG, the view contains the entire DAG.
+    pub fn as_of(self, index: usize) -> RxInput<'a, 'c, A> {
+        let sub_dag = self.sub_dag();
+        let before = sub_dag.before.truncated(index);
+        RxInput(RxSubDAG {
+            index: before.len(),
+            before,
+            id: sub_dag.id,
+            mismatch_policy: sub_dag.mismatch_policy,
+            num_outputs: 0,
+            dirty: sub_dag.dirty,
+            read_flags: sub_dag.read_flags,
+            stale_reads: sub_dag.stale_reads,
+            set_listeners: None
+        })
     }
 }
 
 impl<'a, 'c: 'a, A: Allocator> RxContext<'a, 'c, A> for RxDAGSnapshot<'a, 'c, A> {
     fn sub_dag(self) -> RxSubDAG<'a, 'c, A> {
+        let is_stale = self.0.warn_on_stale_read.get() && self.0.dirty.get();
         RxSubDAG {
-            before: FrozenSlice::from(&self.0.0),
-            index: self.0.0.len(),
-            id: self.0.1
+            stale_reads: is_stale.then_some(&self.0.stale_reads),
+            ..self.0.full_sub_dag()
         }
     }
 }
 
+impl<'a, 'c: 'a, A: Allocator + 'c> ConsistentView<'a, 'c, A> {
+    /// The revision every read is from. See [RxDAG::revision].
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+impl<'a, 'c: 'a, A: Allocator + 'c> RxContext<'a, 'c, A> for ConsistentView<'a, 'c, A> {
+    fn sub_dag(self) -> RxSubDAG<'a, 'c, A> {
+        self.snapshot.sub_dag()
+    }
+}
+
 impl<'a, 'c: 'a, A: Allocator + 'c> MutRxContext<'a, 'c, A> for &'a RxDAG<'c, A> {
     fn sub_dag(self) -> RxSubDAG<'a, 'c, A> {
-        RxDAGSnapshot(self).sub_dag()
+        RxSubDAG {
+            set_listeners: Some(&self.set_listeners),
+            ..self.full_sub_dag()
+        }
     }
 }
 
@@ -328,13 +1751,136 @@ impl<'a, 'c: 'a, A: Allocator> RxContext<'a, 'c, A> for RxInput<'a, 'c, A> {
 }
 
 impl<'a, 'c: 'a, A: Allocator> RxInput<'a, 'c, A> {
+    /// Read a value without making it a dependency: changing it alone won't recompute this computation,
+    /// but when this recomputes for another reason it reads the latest value.
+    pub fn get_untracked<T>(&self, rx: impl Readable<'c, T, A>) -> &'a T {
+        rx.get_untracked(self.0)
+    }
+
+    /// Read a node from an [UntypedRxRef], e.g. one chosen while computing. Like other reads it becomes a dependency.
+    ///
+    /// # Safety
+    /// The node must have type `T`. In debug builds this is checked by type name.
+    pub unsafe fn read_untyped<T>(&self, rx: UntypedRxRef<'c, A>) -> &'a T where A: 'c {
+        debug_assert!(
+            rx.index() >= self.0.before.len() || unsafe { self.0.before.get_unchecked(rx.index()) }.as_node().is_none_or(|node| node.value_type_name() == type_name::<T>()),
+            "RxInput::read_untyped: node has a different type than {}", type_name::<T>()
+        );
+        RxRef::<'c, T, A>::from_raw(rx).get(*self)
+    }
+
+    /// The node this computation outputs, or its first output if it has multiple (e.g. [RxDAG::new_crx2]).
+    ///
+    /// The node can't be read while it's computing (its first computation is before it exists),
+    /// but the ref can be stored and read later.
+    /// Returns `None` if the computation has no outputs (e.g. [RxDAG::run_crx]) or this is a view from [RxDAGSnapshot::as_of].
+    pub fn self_ref(&self) -> Option<UntypedRxRef<'c, A>> {
+        // The edge's outputs immediately follow it
+        (self.0.num_outputs > 0).then(|| UntypedRxRef::from_parts(self.0.index + 1, self.0.id))
+    }
+
+    /// Forget every value read so far, so they're no longer dependencies unless they get read again.
+    ///
+    /// Useful to compare the values a computation reads across runs, e.g. to diagnose dynamic dependencies.
+    pub fn reset_tracking(&self) {
+        self.0.read_flags.clear_before(self.0.before.len());
+    }
+
+    /// Run `f` without making anything it reads a dependency, like calling [RxInput::get_untracked] for every read.
+    /// Values read before or after `f` are still dependencies.
+    pub fn untracked_scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let tracked = self.post_read();
+        let result = f();
+        self.reset_tracking();
+        self.restore_reads(tracked);
+        result
+    }
+
     fn post_read(&self) -> Vec<usize> {
-        let mut results = Vec::new();
-        for (index, current) in self.0.before.iter().enumerate() {
-            if current.post_read() {
-                results.push(index)
+        self.0.read_flags.take_before(self.0.before.len())
+    }
+
+    fn restore_reads(&self, indices: Vec<usize>) {
+        for index in indices {
+            self.0.read_flags.insert(index);
+        }
+    }
+}
+
+/// Recompute `g` once and evaluate the body with a snapshot, e.g.
+/// `with_snapshot!(g, |s| s.read(x) + s.read(y))`. See [RxDAG::with_snapshot].
+pub macro with_snapshot($g:expr, |$s:ident| $body:expr) {
+    $g.with_snapshot(|$s| $body)
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl MismatchPolicy {
+    /// Called when a ref from a different graph is used in `context`.
+    /// Returns whether to use the ref anyway, which is only if the policy is [MismatchPolicy::None] and this is a release build.
+    /// Returns `false` if the policy is [MismatchPolicy::Log], so the caller should skip the operation.
+    pub(crate) fn on_mismatch(self, context: &str) -> bool {
+        match self {
+            MismatchPolicy::None => if cfg!(debug_assertions) {
+                panic!("{}: different graph", context)
+            } else {
+                true
+            },
+            MismatchPolicy::Panic => panic!("{}: different graph", context),
+            MismatchPolicy::Log => {
+                log::error!("{}: different graph", context);
+                false
             }
         }
-        results
     }
-}
\ No newline at end of file
+
+    /// Like [MismatchPolicy::on_mismatch], for an operation which can't be skipped (e.g. it returns the value),
+    /// so this panics under [MismatchPolicy::Log] too.
+    pub(crate) fn on_unskippable_mismatch(self, context: &str) {
+        if !self.on_mismatch(context) {
+            panic!("{}: different graph, and there is no value to return", context)
+        }
+    }
+}
+
+impl EdgePanic {
+    /// The panic message, if it's a string
+    pub fn message(&self) -> Option<&str> {
+        match self.payload.downcast_ref::<&'static str>() {
+            Some(message) => Some(message),
+            None => self.payload.downcast_ref::<String>().map(String::as_str)
+        }
+    }
+}
+
+impl Display for EdgePanic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "edge at index {} panicked", self.index)?;
+        if let Some(message) = self.message() {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for EdgePanic {}
+
+impl Display for CapacityExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RxDAG exceeded its maximum of {} elements", self.max)
+    }
+}
+
+impl Error for CapacityExceeded {}
+
+impl Display for NoInputs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "computed value doesn't read any values, so it would never recompute")
+    }
+}
+
+impl Error for NoInputs {}
@@ -18,5 +18,10 @@ impl<'c, A: Allocator> RxDAGUid<'c, A> {
             RxDAGUid(uid_cell.update(|uid| uid + 1), PhantomData)
         })
     }
+
+    /// The id without the graph's types, e.g. to key maps which don't know them
+    pub(crate) fn as_usize(self) -> usize {
+        self.0
+    }
 }
 
@@ -0,0 +1,168 @@
+use std::alloc::Allocator;
+use std::collections::HashMap;
+use crate::dag::RxDAG;
+use crate::rx_impl::RxDAGElemRef;
+use crate::rx_ref::UntypedRxRef;
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Get the nodes `node` was computed from when it last computed, in graph order. Variables have no dependencies.
+    pub fn dependencies_of(&self, node: UntypedRxRef<'c, A>) -> Vec<UntypedRxRef<'c, A>> {
+        if !self.check_graph(node.graph_id(), "RxDAG::dependencies_of") {
+            return Vec::new();
+        }
+        match self.producing_edge_index(node.index()) {
+            None => Vec::new(),
+            Some(edge_index) => {
+                let edge = match unsafe { self.elems().get_unchecked(edge_index) } {
+                    RxDAGElemRef::Edge(edge) => edge,
+                    RxDAGElemRef::Node(_) => unreachable!("producing_edge_index returned a node")
+                };
+                // Offsets are collected in graph order, so the largest offset is the earliest input
+                edge.input_backwards_offsets().iter().map(|offset| UntypedRxRef::new(self, edge_index - offset)).collect()
+            }
+        }
+    }
+
+    /// Index of the edge which computes the node at `index`, or `None` if it's not computed (a variable or constant).
+    pub(crate) fn producing_edge_index(&self, index: usize) -> Option<usize> {
+        // Outputs are always right after the edge, so the producer is the closest preceding edge if it has enough outputs
+        let mut edge_index = index;
+        while edge_index > 0 {
+            edge_index -= 1;
+            if let RxDAGElemRef::Edge(edge) = unsafe { self.elems().get_unchecked(edge_index) } {
+                return (edge_index + edge.num_outputs() >= index).then_some(edge_index);
+            }
+        }
+        None
+    }
+
+    /// Get every node which would be recomputed if `root` changed, in graph order (not including `root`).
+    ///
+    /// This is based on the current dependencies: computed values may read different inputs after they recompute.
+    pub fn transitive_dependents(&self, root: UntypedRxRef<'c, A>) -> Vec<UntypedRxRef<'c, A>> {
+        if !self.check_graph(root.graph_id(), "RxDAG::transitive_dependents") {
+            return Vec::new();
+        }
+        // Edges only read from before and write to after, so we can find every dependent in one forward pass
+        let mut is_affected = vec![false; self.elems().len()];
+        is_affected[root.index()] = true;
+        let mut dependents = Vec::new();
+        for (index, elem) in self.elems().iter().enumerate() {
+            match elem {
+                RxDAGElemRef::Node(_) => if is_affected[index] && index != root.index() {
+                    dependents.push(UntypedRxRef::new(self, index));
+                },
+                RxDAGElemRef::Edge(edge) => {
+                    if edge.input_backwards_offsets().iter().any(|offset| is_affected[index - offset]) {
+                        // Outputs are always right after the edge
+                        is_affected[index + 1..=index + edge.num_outputs()].fill(true);
+                    }
+                }
+            }
+        }
+        dependents
+    }
+
+    /// Get every node which `root` was computed from, directly or indirectly, in graph order (not including `root`).
+    /// These are the nodes which, if changed, may change `root`.
+    ///
+    /// This is based on the current dependencies: computed values may read different inputs after they recompute.
+    pub fn transitive_inputs(&self, root: UntypedRxRef<'c, A>) -> Vec<UntypedRxRef<'c, A>> {
+        if !self.check_graph(root.graph_id(), "RxDAG::transitive_inputs") {
+            return Vec::new();
+        }
+        // The inputs are what the needed edges read. Other needed nodes are only outputs of those edges
+        let is_needed = self.transitive_inputs_mask(root.index());
+        let mut is_input = vec![false; root.index()];
+        for (index, elem) in self.elems().iter().enumerate().take(root.index()) {
+            if let RxDAGElemRef::Edge(edge) = elem {
+                if is_needed[index] {
+                    for offset in edge.input_backwards_offsets() {
+                        is_input[index - offset] = true;
+                    }
+                }
+            }
+        }
+        is_input.into_iter().enumerate()
+            .filter(|(_, is_input)| *is_input)
+            .map(|(index, _)| UntypedRxRef::new(self, index))
+            .collect()
+    }
+
+    /// [RxDAG::transitive_inputs] which aren't computed: the variables (and constants) `root` ultimately depends on.
+    pub fn transitive_input_vars(&self, root: UntypedRxRef<'c, A>) -> Vec<UntypedRxRef<'c, A>> {
+        self.transitive_inputs(root).into_iter().filter(|input| self.producing_edge_index(input.index()).is_none()).collect()
+    }
+
+    /// Length of the longest chain of computed values, each computed from the previous, starting from a variable.
+    /// This is how many edges a change may need to propagate through in one recompute. Side-effects aren't counted.
+    ///
+    /// This is based on the current dependencies: computed values may read different inputs after they recompute.
+    pub fn critical_path_length(&self) -> usize {
+        // Edges only read from before, so we can find every depth in one forward pass
+        let mut depths = vec![0; self.elems().len()];
+        let mut max_depth = 0;
+        for (index, elem) in self.elems().iter().enumerate() {
+            if let RxDAGElemRef::Edge(edge) = elem {
+                if edge.num_outputs() == 0 {
+                    continue;
+                }
+                let depth = 1 + edge.input_backwards_offsets().iter().map(|offset| depths[index - offset]).max().unwrap_or(0);
+                // Outputs are always right after the edge
+                depths[index + 1..=index + edge.num_outputs()].fill(depth);
+                max_depth = max_depth.max(depth);
+            }
+        }
+        max_depth
+    }
+
+    /// Find pairs of edges which compute values from the same inputs, so they may compute the same thing
+    /// and be mergeable. Returns the indices of each pair in graph order.
+    ///
+    /// Closures can't be compared, so this is only a heuristic. Side-effects and edges without inputs aren't reported.
+    pub fn find_redundant_edges(&self) -> Vec<(usize, usize)> {
+        let mut edges_by_inputs = HashMap::<(Vec<usize>, usize), Vec<usize>>::new();
+        let mut redundant_edges = Vec::new();
+        for (index, elem) in self.elems().iter().enumerate() {
+            if let RxDAGElemRef::Edge(edge) = elem {
+                if edge.num_outputs() == 0 || edge.input_backwards_offsets().is_empty() {
+                    continue;
+                }
+                // Offsets are relative to the edge, so compare the actual inputs
+                let mut inputs = edge.input_backwards_offsets().iter().map(|offset| index - offset).collect::<Vec<_>>();
+                inputs.sort_unstable();
+                let same_edges = edges_by_inputs.entry((inputs, edge.num_outputs())).or_default();
+                redundant_edges.extend(same_edges.iter().map(|other_index| (*other_index, index)));
+                same_edges.push(index);
+            }
+        }
+        redundant_edges.sort_unstable();
+        redundant_edges
+    }
+
+    /// Which elements `root` transitively depends on (including `root`): its producing edge, the edge's inputs
+    /// and outputs, their producing edges, and so on.
+    pub(crate) fn transitive_inputs_mask(&self, root: usize) -> Vec<bool> {
+        // Edges only read from before, so we can find every input in one backward pass
+        let mut is_needed = vec![false; self.elems().len()];
+        is_needed[root] = true;
+        for index in (0..=root).rev() {
+            if !is_needed[index] {
+                continue;
+            }
+            match unsafe { self.elems().get_unchecked(index) } {
+                RxDAGElemRef::Node(_) => if let Some(edge_index) = self.producing_edge_index(index) {
+                    is_needed[edge_index] = true;
+                },
+                RxDAGElemRef::Edge(edge) => {
+                    for offset in edge.input_backwards_offsets() {
+                        is_needed[index - offset] = true;
+                    }
+                    // Outputs are always right after the edge
+                    is_needed[index + 1..=index + edge.num_outputs()].fill(true);
+                }
+            }
+        }
+        is_needed
+    }
+}
@@ -0,0 +1,67 @@
+use std::alloc::{Allocator, Global};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use crate::clone_set_fn::CloneSetFn;
+use crate::dag::MutRxContext;
+use crate::rx_ref::{RxRef, Var, DVar};
+
+/// Sets multiple parts of a variable at once. See [Var::derive_transaction].
+#[derive(Debug)]
+pub struct DeriveTxn<'c, T, A: Allocator = Global> {
+    source: RxRef<'c, T, A>,
+    working: RefCell<T>
+}
+
+impl<'c, T: Clone, A: Allocator + 'c> Var<'c, T, A> {
+    /// Set multiple views from [Var::derive_using_clone] at once, cloning the value only once.
+    ///
+    /// `edits` sets the views through the [DeriveTxn], which edits a single copy of the latest value.
+    /// The copy is set when `edits` returns. Like `set` the changes only actually reflect on recompute.
+    pub fn derive_transaction<'a>(self, c: impl MutRxContext<'a, 'c, A>, edits: impl FnOnce(&DeriveTxn<'c, T, A>)) where 'c: 'a {
+        let source = self.raw();
+        self.modify(c, move |latest| {
+            let txn = DeriveTxn {
+                source,
+                working: RefCell::new(latest.clone())
+            };
+            edits(&txn);
+            txn.working.into_inner()
+        })
+    }
+
+    /// Like [Var::derive_transaction], but `validate` checks the value after all edits,
+    /// and if it returns an error, nothing is set and the error is returned.
+    pub fn derive_transaction_checked<'a, E>(self, c: impl MutRxContext<'a, 'c, A>, edits: impl FnOnce(&DeriveTxn<'c, T, A>), validate: impl FnOnce(&T) -> Result<(), E>) -> Result<(), E> where 'c: 'a {
+        let source = self.raw();
+        let mut result = Ok(());
+        source.modify_cow(c, |latest| {
+            let txn = DeriveTxn {
+                source,
+                working: RefCell::new(latest.clone())
+            };
+            edits(&txn);
+            let working = txn.working.into_inner();
+            match validate(&working) {
+                Ok(()) => Cow::Owned(working),
+                Err(error) => {
+                    result = Err(error);
+                    Cow::Borrowed(latest)
+                }
+            }
+        });
+        result
+    }
+}
+
+impl<'c, T: Clone, A: Allocator + 'c> DeriveTxn<'c, T, A> {
+    /// Set the part of the value `view` gets.
+    pub fn set<U, GetFn: Fn(&T) -> &U, SetFn: Fn(&mut T, U)>(&self, view: &DVar<'c, T, U, GetFn, CloneSetFn<T, U, SetFn>, A>, value: U) {
+        debug_assert!(view.source.same_node(self.source), "DeriveTxn::set: view is of a different variable");
+        view.set.set_in_place(&mut self.working.borrow_mut(), value);
+    }
+
+    /// Edit the value directly.
+    pub fn modify(&self, edit: impl FnOnce(&mut T)) {
+        edit(&mut self.working.borrow_mut())
+    }
+}
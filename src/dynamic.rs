@@ -0,0 +1,38 @@
+use std::alloc::Allocator;
+use std::any::{Any, type_name};
+use crate::dag::{RxDAG, RxContext};
+use crate::rx_ref::{RxRef, UntypedRxRef};
+
+/// Clones the value at the pointer, which must be the value of the node it was registered for
+pub(crate) type AnyCloner = unsafe fn(*const ()) -> Box<dyn Any>;
+
+/// A node's registered [AnyCloner] and the name of the type it clones, to check the node still has that type
+pub(crate) type DynamicEntry = (&'static str, AnyCloner);
+
+pub(crate) unsafe fn clone_any<T: Clone + 'static>(value: *const ()) -> Box<dyn Any> {
+    Box::new((*(value as *const T)).clone())
+}
+
+impl<'c, A: Allocator + 'c> RxDAG<'c, A> {
+    /// Allow reading the node with [RxDAG::get_boxed], e.g. for a scripting layer which doesn't know its type.
+    pub fn register_dynamic<T: Clone + 'static>(&self, rx: RxRef<'c, T, A>) {
+        // Not the mismatch policy: a cloner registered at another graph's index would clone the wrong type
+        assert!(rx.raw().graph_id() == self.id(), "RxDAG::register_dynamic: different graph");
+        self.dynamic_cloners().borrow_mut().insert(rx.raw().index(), (type_name::<T>(), clone_any::<T>));
+    }
+
+    /// Read a clone of the node's value without knowing its type, which you can downcast.
+    /// Returns `None` unless the node was registered by [RxDAG::register_dynamic].
+    ///
+    /// Like other reads, inside a computation this makes the node a dependency.
+    ///
+    /// Panics if `rx` or `c` is from a different graph, even in release builds.
+    pub fn get_boxed<'a>(&self, rx: UntypedRxRef<'c, A>, c: impl RxContext<'a, 'c, A>) -> Option<Box<dyn Any>> where 'c: 'a {
+        let graph = c.sub_dag();
+        assert!(rx.graph_id() == self.id() && graph.id == self.id(), "RxDAG::get_boxed: different graph");
+        let (type_name, clone) = *self.dynamic_cloners().borrow().get(&rx.index())?;
+        let node = rx.read_rx(graph);
+        assert_eq!(node.value_type_name(), type_name, "RxDAG::get_boxed: node has a different type than it was registered with");
+        Some(unsafe { clone(node._value_ptr_dyn()) })
+    }
+}
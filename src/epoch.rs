@@ -0,0 +1,70 @@
+use std::alloc::{Allocator, Global};
+use std::collections::BTreeSet;
+use crate::dag::RxDAG;
+use crate::rx_impl::RxDAGElemRef;
+use crate::rx_ref::UntypedRxRef;
+
+/// Records the nodes which changed since [RxDAG::begin_epoch].
+#[derive(Debug)]
+pub(crate) struct Epoch {
+    start_revision: u64,
+    changed: BTreeSet<usize>
+}
+
+/// The nodes which changed in every recompute of an epoch. See [RxDAG::end_epoch].
+#[derive(Debug, Clone)]
+pub struct EpochSummary<'c, A: Allocator = Global> {
+    /// Revision when the epoch began (see [RxDAG::revision]). Recomputes after this are in the epoch.
+    pub start_revision: u64,
+    /// Revision when the epoch ended
+    pub end_revision: u64,
+    /// Nodes which changed in any recompute of the epoch, in graph order
+    pub changed: Vec<UntypedRxRef<'c, A>>
+}
+
+impl<'c, A: Allocator> EpochSummary<'c, A> {
+    /// Number of recomputes in the epoch
+    pub fn num_recomputes(&self) -> u64 {
+        self.end_revision - self.start_revision
+    }
+
+    /// Whether the node changed in any recompute of the epoch
+    pub fn did_change(&self, rx: UntypedRxRef<'c, A>) -> bool {
+        self.changed.iter().any(|changed| changed.same_node(rx))
+    }
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Start grouping recomputes into an epoch, e.g. all the recomputes caused by one user interaction.
+    /// [RxDAG::end_epoch] returns every node which changed in between. Calling this again restarts the epoch.
+    pub fn begin_epoch(&mut self) {
+        let start_revision = self.revision();
+        *self.epoch_mut() = Some(Epoch {
+            start_revision,
+            changed: BTreeSet::new()
+        });
+    }
+
+    /// Stop the epoch started by [RxDAG::begin_epoch] and return the nodes which changed in any of its recomputes.
+    ///
+    /// Panics if there is no epoch.
+    pub fn end_epoch(&mut self) -> EpochSummary<'c, A> {
+        let epoch = self.epoch_mut().take().expect("RxDAG::end_epoch called without begin_epoch");
+        EpochSummary {
+            start_revision: epoch.start_revision,
+            end_revision: self.revision(),
+            changed: epoch.changed.into_iter().map(|index| UntypedRxRef::new(self, index)).collect()
+        }
+    }
+
+    /// Record the nodes which changed in the current recompute
+    pub(crate) fn record_epoch(&mut self) {
+        if self.epoch_mut().is_none() {
+            return;
+        }
+        let recomputed = self.recomputed().iter().copied().filter(|index| {
+            matches!(self.elems().get(*index), Some(RxDAGElemRef::Node(_)))
+        }).collect::<Vec<_>>();
+        self.epoch_mut().as_mut().unwrap().changed.extend(recomputed);
+    }
+}
@@ -0,0 +1,99 @@
+use std::alloc::Allocator;
+use std::any::type_name;
+use std::fmt::{Debug, Display, Formatter};
+use crate::dag::RxDAG;
+use crate::rx_impl::RxDAGElemRef;
+use crate::rx_ref::{RxRef, UntypedRxRef};
+
+/// Formats the value at the pointer, which must be the value of the node it was registered for
+pub(crate) type DebugFormatter = unsafe fn(*const ()) -> String;
+
+/// A node's registered [DebugFormatter] and the name of the type it formats, to check the node still has that type
+pub(crate) type DebugEntry = (&'static str, DebugFormatter);
+
+unsafe fn format_debug<T: Debug>(value: *const ()) -> String {
+    format!("{:?}", *(value as *const T))
+}
+
+/// A node's value and the values it was computed from. See [RxDAG::explain].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    /// The explained node
+    pub node: NodeExplanation,
+    /// The nodes it was computed from when it last computed, in graph order
+    pub inputs: Vec<NodeExplanation>
+}
+
+/// A node in an [Explanation]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeExplanation {
+    /// Index of the node in the DAG
+    pub index: usize,
+    /// Whether this is a computed value (otherwise it's a variable)
+    pub is_computed: bool,
+    /// Name of the value's type
+    pub type_name: &'static str,
+    /// The current value formatted with [Debug], if it was registered by [RxDAG::register_debug] and is computed
+    pub value: Option<String>
+}
+
+impl<'c, A: Allocator + 'c> RxDAG<'c, A> {
+    /// Allow [RxDAG::explain] to show the node's value.
+    pub fn register_debug<T: Debug>(&self, rx: RxRef<'c, T, A>) {
+        // Not the mismatch policy: a formatter registered at another graph's index would format the wrong type
+        assert!(rx.raw().graph_id() == self.id(), "RxDAG::register_debug: different graph");
+        self.debug_formatters().borrow_mut().insert(rx.raw().index(), (type_name::<T>(), format_debug::<T>));
+    }
+
+    /// Get the node's current value and the values of the nodes it was computed from, to debug why it has its value.
+    /// Only values of nodes registered by [RxDAG::register_debug] are shown.
+    ///
+    /// This doesn't recompute, and doesn't make anything a dependency.
+    ///
+    /// Panics if `rx` is from a different graph, even in release builds.
+    pub fn explain(&self, rx: UntypedRxRef<'c, A>) -> Explanation {
+        assert!(rx.graph_id() == self.id(), "RxDAG::explain: different graph");
+        Explanation {
+            node: self.explain_node(rx.index()),
+            inputs: self.dependencies_of(rx).into_iter().map(|input| self.explain_node(input.index())).collect()
+        }
+    }
+
+    fn explain_node(&self, index: usize) -> NodeExplanation {
+        let node = match self.elems().get(index) {
+            Some(RxDAGElemRef::Node(node)) => node,
+            _ => unreachable!("expected a node at index {}", index)
+        };
+        // The formatter is only used if the node still has the registered type
+        let format = self.debug_formatters().borrow().get(&index)
+            .filter(|(type_name, _)| *type_name == node.value_type_name())
+            .map(|(_, format)| *format);
+        NodeExplanation {
+            index,
+            is_computed: self.producing_edge_index(index).is_some(),
+            type_name: node.value_type_name(),
+            value: format.filter(|_| node.is_initialized()).map(|format| unsafe { format(node._value_ptr_dyn()) })
+        }
+    }
+}
+
+impl Display for NodeExplanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.is_computed { "crx" } else { "var" };
+        write!(f, "{}#{}: {}", kind, self.index, self.type_name)?;
+        if let Some(value) = &self.value {
+            write!(f, " = {}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.node)?;
+        for input in &self.inputs {
+            writeln!(f, "  {}", input)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use std::alloc::Allocator;
+use std::sync::mpsc::{channel, Sender, Receiver};
+use derivative::Derivative;
+use crate::dag::RxDAG;
+use crate::rx_ref::Var;
+
+/// Sets a variable from outside the DAG, e.g. from a GUI callback or another thread.
+/// See [RxDAG::new_external_input].
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct ExternalSink<T>(Sender<T>);
+
+/// Applies values from an [ExternalSink] to its variable
+pub(crate) type ExternalInput<'c, A> = Box<dyn FnMut(&RxDAG<'c, A>) + 'c>;
+
+impl<T> ExternalSink<T> {
+    /// Set the variable to `value` on the next recompute. Later pushes win.
+    ///
+    /// Returns `false` if the DAG was dropped, so the value is discarded.
+    pub fn push(&self, value: T) -> bool {
+        self.0.send(value).is_ok()
+    }
+}
+
+impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
+    /// Create a variable which can be set through the returned [ExternalSink], which is [Send] (if `T` is)
+    /// so you can push values from other threads or external event sources.
+    ///
+    /// Pushed values are set at the start of the next recompute.
+    /// They don't make [RxDAG::is_dirty] true until then, since they aren't set before.
+    pub fn new_external_input<T: 'c>(&self, initial: T) -> (Var<'c, T, A>, ExternalSink<T>) {
+        let var = self.new_var(initial);
+        let (sender, receiver): (Sender<T>, Receiver<T>) = channel();
+        self.external_inputs().borrow_mut().push(Box::new(move |graph| {
+            for value in receiver.try_iter() {
+                var.set(graph, value);
+            }
+        }));
+        (var, ExternalSink(sender))
+    }
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Set values pushed to [ExternalSink]s
+    pub(crate) fn apply_external_inputs(&self) {
+        for apply in self.external_inputs().borrow_mut().iter_mut() {
+            apply(self);
+        }
+    }
+}
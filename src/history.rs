@@ -0,0 +1,81 @@
+use std::alloc::Allocator;
+use std::any::Any;
+use std::collections::VecDeque;
+use crate::dag::RxDAG;
+use crate::dynamic::{AnyCloner, clone_any};
+use crate::rx_impl::RxDAGElemRef;
+use crate::rx_ref::{RxRef, UntypedRxRef};
+
+/// Past values of a node. See [RxDAG::track_history].
+#[derive(Debug)]
+pub(crate) struct NodeHistory {
+    capacity: usize,
+    clone: AnyCloner,
+    // Revision each value was committed at, oldest first
+    entries: VecDeque<(u64, Box<dyn Any>)>
+}
+
+impl NodeHistory {
+    fn push(&mut self, revision: u64, value: Box<dyn Any>) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((revision, value));
+    }
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Start recording the node's last `capacity` values, so [RxDAG::get_at] can read what it was at a past revision.
+    /// Calling this again resets the recording.
+    pub fn track_history<T: Clone + 'static>(&self, rx: RxRef<'c, T, A>, capacity: usize) where A: 'c {
+        assert!(capacity > 0, "RxDAG::track_history: capacity must be positive");
+        if !self.check_graph(rx.raw().graph_id(), "RxDAG::track_history") {
+            return;
+        }
+        let index = rx.raw().index();
+        let mut history = NodeHistory {
+            capacity,
+            clone: clone_any::<T>,
+            entries: VecDeque::with_capacity(capacity)
+        };
+        history.push(self.revision(), unsafe { clone_any::<T>(self.node_value_ptr(index)) });
+        self.history().borrow_mut().insert(index, history);
+    }
+
+    /// Clone of the value the node had at `revision` (see [RxDAG::revision]).
+    ///
+    /// Returns `None` if the node's history isn't tracked (see [RxDAG::track_history]),
+    /// `revision` is before it was tracked or no longer in the recorded values, or `revision` is in the future.
+    ///
+    /// Panics if `T` isn't the node's type.
+    pub fn get_at<T: Clone + 'static>(&self, rx: UntypedRxRef<'c, A>, revision: u64) -> Option<T> {
+        if revision > self.revision() {
+            return None;
+        }
+        let history = self.history().borrow();
+        let (_, value) = history.get(&rx.index())?.entries.iter().rev().find(|(committed, _)| *committed <= revision)?;
+        Some(value.downcast_ref::<T>().expect("RxDAG::get_at: wrong type").clone())
+    }
+
+    /// Record the values of tracked nodes which changed in the current recompute
+    pub(crate) fn record_history(&self) {
+        let mut history = self.history().borrow_mut();
+        if history.is_empty() {
+            return;
+        }
+        for index in self.recomputed().iter().copied() {
+            if let Some(node_history) = history.get_mut(&index) {
+                let value = unsafe { (node_history.clone)(self.node_value_ptr(index)) };
+                node_history.push(self.revision(), value);
+            }
+        }
+    }
+
+    fn node_value_ptr(&self, index: usize) -> *const () {
+        match self.elems().get(index) {
+            // Doesn't mark the node read, since that would make it a dependency of the next computation
+            Some(RxDAGElemRef::Node(node)) => unsafe { node._value_ptr_dyn() },
+            _ => unreachable!("expected a node at index {}", index)
+        }
+    }
+}
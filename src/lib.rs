@@ -29,7 +29,42 @@ pub(crate) mod dag_uid;
 pub(crate) mod rx_impl;
 pub(crate) mod rx_ref;
 pub(crate) mod clone_set_fn;
+pub(crate) mod changed_inputs;
+pub(crate) mod var_snapshot;
+pub(crate) mod shape;
+pub(crate) mod dependents;
+pub(crate) mod restricted_view;
+pub(crate) mod readable;
+pub(crate) mod stats;
+pub(crate) mod stepper;
+pub(crate) mod reactive_impl;
+pub(crate) mod collect;
+pub(crate) mod volatility;
+pub(crate) mod derive_txn;
+pub(crate) mod dynamic;
+pub(crate) mod builder;
+pub(crate) mod history;
+pub(crate) mod pending_sets;
+pub(crate) mod on_set;
+pub(crate) mod external;
+pub(crate) mod explain;
+pub(crate) mod epoch;
 
 pub use dag::*;
 pub use rx_ref::*;
-pub use clone_set_fn::*;
\ No newline at end of file
+pub use clone_set_fn::*;
+pub use changed_inputs::*;
+pub use var_snapshot::*;
+pub use shape::*;
+pub use restricted_view::*;
+pub use readable::*;
+pub use stats::*;
+pub use stepper::*;
+pub use reactive_impl::*;
+pub use collect::*;
+pub use derive_txn::*;
+pub use builder::*;
+pub use pending_sets::*;
+pub use external::*;
+pub use explain::*;
+pub use epoch::*;
\ No newline at end of file
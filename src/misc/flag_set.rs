@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Set of indices which can be inserted into through a shared reference, stored one bit per index.
+///
+/// Finding the set indices checks 64 at a time, so it's much faster than checking a flag in each element,
+/// especially when the elements are separate allocations.
+#[derive(Default)]
+pub struct FlagSet(RefCell<Vec<u64>>);
+
+impl FlagSet {
+    /// Constructs a new, empty set.
+    pub fn new() -> Self {
+        Self(RefCell::new(Vec::new()))
+    }
+
+    /// Allow indices up to `len` (exclusive).
+    pub fn grow(&self, len: usize) {
+        let mut words = self.0.borrow_mut();
+        let num_words = len.div_ceil(WORD_BITS);
+        if words.len() < num_words {
+            words.resize(num_words, 0);
+        }
+    }
+
+    /// Add the index. **Panics** if it's not allowed by [FlagSet::grow].
+    pub fn insert(&self, index: usize) {
+        self.0.borrow_mut()[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+    }
+
+    /// Remove the index, if it's allowed by [FlagSet::grow].
+    pub fn remove(&self, index: usize) {
+        if let Some(word) = self.0.borrow_mut().get_mut(index / WORD_BITS) {
+            *word &= !(1 << (index % WORD_BITS));
+        }
+    }
+
+    /// Remove and return the indices before `len`, in order.
+    pub fn take_before(&self, len: usize) -> Vec<usize> {
+        let mut indices = Vec::new();
+        self.for_each_word_before(len, |word_index, word, mask| {
+            let mut bits = *word & mask;
+            *word &= !mask;
+            while bits != 0 {
+                indices.push(word_index * WORD_BITS + bits.trailing_zeros() as usize);
+                // Clear the lowest bit
+                bits &= bits - 1;
+            }
+        });
+        indices
+    }
+
+    /// Remove the indices before `len`.
+    pub fn clear_before(&self, len: usize) {
+        self.for_each_word_before(len, |_, word, mask| *word &= !mask);
+    }
+
+    /// Remove every index.
+    pub fn clear(&self) {
+        self.0.borrow_mut().fill(0);
+    }
+
+    /// Calls `f` with each nonzero word containing indices before `len`, and the mask of those indices in the word
+    fn for_each_word_before(&self, len: usize, mut f: impl FnMut(usize, &mut u64, u64)) {
+        let mut words = self.0.borrow_mut();
+        let num_words = len.div_ceil(WORD_BITS).min(words.len());
+        for (word_index, word) in words[..num_words].iter_mut().enumerate() {
+            if *word == 0 {
+                continue;
+            }
+            let num_bits = (len - word_index * WORD_BITS).min(WORD_BITS);
+            let mask = if num_bits == WORD_BITS { u64::MAX } else { (1 << num_bits) - 1 };
+            f(word_index, word, mask);
+        }
+    }
+}
+
+impl Debug for FlagSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let words = self.0.borrow();
+        f.debug_set()
+            .entries(words.iter().enumerate().flat_map(|(word_index, word)| {
+                (0..WORD_BITS).filter(move |bit| word & (1 << bit) != 0).map(move |bit| word_index * WORD_BITS + bit)
+            }))
+            .finish()
+    }
+}
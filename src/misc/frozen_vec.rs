@@ -356,6 +356,11 @@ impl<'a, T: StableDeref2> FrozenSlice<'a, T> {
         self.0.len()
     }
 
+    /// Returns the first `len` elements, or the entire slice if it's shorter.
+    pub fn truncated(&self, len: usize) -> FrozenSlice<'a, T> {
+        FrozenSlice(&self.0[..len.min(self.0.len())])
+    }
+
     // TODO add more
 }
 
@@ -1,4 +1,5 @@
 pub mod assert_variance;
+pub mod flag_set;
 pub mod frozen_vec;
 pub mod slice_split3;
 pub mod stable_deref2;
\ No newline at end of file
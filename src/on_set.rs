@@ -0,0 +1,71 @@
+use std::alloc::Allocator;
+use std::any::type_name;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use crate::dag::{RxDAG, RxSubDAG};
+use crate::rx_ref::{Var, UntypedRxRef};
+
+/// Called with a pointer to the value a variable is set to
+type SetListener<'c> = Box<dyn FnMut(*const ()) + 'c>;
+
+/// The graph id, index, and value type name of a variable, so a ref from another graph with the same index
+/// (allowed by [crate::MismatchPolicy::None] in release builds) never calls listeners with the wrong type
+type SetKey = (usize, usize, &'static str);
+
+/// Listeners for when each variable is set. See [Var::on_set].
+#[derive(Default)]
+pub(crate) struct SetListeners<'c>(RefCell<HashMap<SetKey, Vec<SetListener<'c>>>>);
+
+/// Calls [SetListeners] without naming their lifetime, so [RxSubDAG] stays covariant
+pub(crate) trait NotifySet: Debug {
+    fn notify_dyn(&self, key: SetKey, value: *const ());
+}
+
+impl<'c> NotifySet for SetListeners<'c> {
+    fn notify_dyn(&self, key: SetKey, value: *const ()) {
+        if let Some(listeners) = self.0.borrow_mut().get_mut(&key) {
+            for listener in listeners {
+                listener(value);
+            }
+        }
+    }
+}
+
+impl<'c> Debug for SetListeners<'c> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.0.borrow().iter().map(|((_, index, _), listeners)| (index, listeners.len())))
+            .finish()
+    }
+}
+
+fn set_key<'c, T, A: Allocator>(var: UntypedRxRef<'c, A>) -> SetKey {
+    (var.graph_id().as_usize(), var.index(), type_name::<T>())
+}
+
+impl<'a, 'c: 'a, A: Allocator> RxSubDAG<'a, 'c, A> {
+    /// Call the listeners of `var` with the value it's set to, if this slice is from setting through the [RxDAG].
+    /// Call after [UntypedRxRef::get_rx] so the graph is checked first
+    pub(crate) fn notify_set<T>(self, var: UntypedRxRef<'c, A>, value: &T) {
+        if let Some(listeners) = self.set_listeners {
+            listeners.notify_dyn(set_key::<T, A>(var), value as *const T as *const ());
+        }
+    }
+}
+
+impl<'c, T: 'c, A: Allocator + 'c> Var<'c, T, A> {
+    /// Run `f` with the new value whenever the variable is set (including through [Var::modify] and derived views).
+    ///
+    /// Unlike [RxDAG::run_crx], this runs immediately when the variable is set, before the DAG recomputes,
+    /// and it runs for every set, even if the value is set again before recomputing or doesn't change.
+    /// `f` must not set variables.
+    pub fn on_set(self, g: &RxDAG<'c, A>, mut f: impl FnMut(&T) + 'c) {
+        if !g.check_graph(self.raw().raw().graph_id(), "Var::on_set") {
+            return;
+        }
+        g.set_listeners().0.borrow_mut().entry(set_key::<T, A>(self.raw().raw())).or_default().push(Box::new(move |value| {
+            f(unsafe { &*(value as *const T) })
+        }));
+    }
+}
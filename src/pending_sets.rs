@@ -0,0 +1,60 @@
+use std::alloc::{Allocator, Global};
+use std::fmt::{Debug, Formatter};
+use crate::dag::RxDAG;
+use crate::rx_ref::Var;
+
+/// Sets one [Var] when applied
+type PendingSet<'c, A> = Box<dyn FnOnce(&RxDAG<'c, A>) + Send + 'c>;
+
+/// Values to set on [Var]s, decided without access to the [RxDAG] (e.g. on another thread).
+/// Apply them with [RxDAG::apply_pending].
+pub struct PendingSets<'c, A: Allocator = Global> {
+    sets: Vec<PendingSet<'c, A>>
+}
+
+impl<'c, A: Allocator + 'c> PendingSets<'c, A> {
+    /// Create an empty set of values.
+    pub fn new() -> Self {
+        Self {
+            sets: Vec::new()
+        }
+    }
+
+    /// Set `var` to `value` when applied. Later pushes to the same variable win.
+    pub fn push<T: Send + 'c>(&mut self, var: Var<'c, T, A>, value: T) where A: Send {
+        self.sets.push(Box::new(move |graph| var.set(graph, value)));
+    }
+
+    /// Number of values to set
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Whether there are no values to set
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+}
+
+impl<'c, A: Allocator + 'c> Default for PendingSets<'c, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'c, A: Allocator> Debug for PendingSets<'c, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingSets")
+            .field("len", &self.sets.len())
+            .finish()
+    }
+}
+
+impl<'c, A: Allocator + 'c> RxDAG<'c, A> {
+    /// Set every value in `sets`, in the order they were pushed. Like `set` the changes only actually reflect on recompute.
+    pub fn apply_pending(&self, sets: PendingSets<'c, A>) {
+        for set in sets.sets {
+            set(self);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+/// Declares a struct of reactive values: variables ([Var](crate::Var)s) and methods computed from them
+/// ([CRx](crate::CRx)s), and a constructor which creates them in an [RxDAG](crate::RxDAG).
+///
+/// The constructor takes the graph and initial values of the variables.
+/// It must be named explicitly (e.g. `pub fn new;`) because of macro hygiene.
+/// Each computed method lists the fields it reads, which are available in its body as references.
+/// A computed method can read variables and the computed methods declared before it.
+/// Each method only depends on the fields it reads, so it only recomputes when they change.
+///
+/// ```
+/// #![feature(decl_macro)]
+/// use mini_rx::*;
+///
+/// reactive_impl! {
+///     pub struct Rect<'c> {
+///         pub width: u32,
+///         pub height: u32
+///     }
+///     pub fn new;
+///     computed {
+///         pub fn area(width, height) -> u32 { width * height }
+///         pub fn is_big(area) -> bool { *area > 100 }
+///     }
+/// }
+///
+/// let mut g = RxDAG::new();
+/// let rect = Rect::new(&g, 2, 3);
+/// assert_eq!(*rect.area.get(g.now()), 6);
+/// rect.width.set(&g, 50);
+/// assert_eq!(*rect.is_big.get(g.now()), true);
+/// ```
+pub macro reactive_impl {
+    (
+        $vis:vis struct $name:ident<$lt:lifetime> {
+            $($var_vis:vis $var:ident: $var_ty:ty),* $(,)?
+        }
+        $ctor_vis:vis fn $ctor:ident;
+        computed {
+            $($crx_vis:vis fn $crx:ident($($input:ident),* $(,)?) -> $crx_ty:ty $body:block)*
+        }
+    ) => {
+        $vis struct $name<$lt> {
+            $($var_vis $var: $crate::Var<$lt, $var_ty>,)*
+            $($crx_vis $crx: $crate::CRx<$lt, $crx_ty>,)*
+        }
+
+        impl<$lt> $name<$lt> {
+            /// Create the variables with the initial values, and the computed values, in `graph`.
+            #[allow(clippy::too_many_arguments)]
+            $ctor_vis fn $ctor(graph: &$crate::RxDAG<$lt>, $($var: $var_ty),*) -> Self {
+                $(let $var = graph.new_var($var);)*
+                $(let $crx = graph.new_crx(move |g| {
+                    $(let $input = $input.get(g);)*
+                    $body
+                });)*
+                Self {
+                    $($var,)*
+                    $($crx,)*
+                }
+            }
+        }
+    }
+}
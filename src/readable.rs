@@ -0,0 +1,74 @@
+use std::alloc::{Allocator, Global};
+use crate::dag::{RxContext, RxSubDAG};
+use crate::rx_ref::{RxRef, Var, CRx, DVar, DCRx};
+
+/// A value you can read from an [RxDAG](crate::RxDAG): [RxRef], [Var], [CRx], [DVar] or [DCRx].
+pub trait Readable<'c, T, A: Allocator = Global> {
+    /// Read the value
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a;
+
+    /// Read the value without marking it read, so it's not a dependency.
+    /// Use [RxInput::get_untracked](crate::RxInput::get_untracked) instead.
+    #[doc(hidden)]
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a;
+}
+
+impl<'c, T, A: Allocator + 'c> Readable<'c, T, A> for RxRef<'c, T, A> {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        RxRef::get(*self, c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        RxRef::get_untracked(*self, graph)
+    }
+}
+
+impl<'c, T, A: Allocator + 'c> Readable<'c, T, A> for Var<'c, T, A> {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        Var::get(*self, c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        self.raw().get_untracked(graph)
+    }
+}
+
+impl<'c, T, A: Allocator + 'c> Readable<'c, T, A> for CRx<'c, T, A> {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        CRx::get(*self, c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        self.raw().get_untracked(graph)
+    }
+}
+
+impl<'c, S: 'c, T, GetFn: Fn(&S) -> &T, SetFn: Fn(&S, T) -> S, A: Allocator + 'c> Readable<'c, T, A> for DVar<'c, S, T, GetFn, SetFn, A> {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        DVar::get(self, c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        (self.get)(self.source.get_untracked(graph))
+    }
+}
+
+impl<'c, S: 'c, T, GetFn: Fn(&S) -> &T, A: Allocator + 'c> Readable<'c, T, A> for DCRx<'c, S, T, GetFn, A> {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        DCRx::get(self, c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        (self.get)(self.source.get_untracked(graph))
+    }
+}
+
+impl<'c, T, A: Allocator, R: Readable<'c, T, A>> Readable<'c, T, A> for &R {
+    fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
+        R::get(self, c)
+    }
+
+    fn get_untracked<'a>(&self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        R::get_untracked(self, graph)
+    }
+}
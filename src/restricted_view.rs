@@ -0,0 +1,51 @@
+use std::alloc::{Allocator, Global};
+use std::collections::HashSet;
+use derivative::Derivative;
+use crate::dag::{RxDAG, RxDAGSnapshot};
+use crate::dag_uid::RxDAGUid;
+use crate::rx_ref::{RxRef, UntypedRxRef};
+
+/// Allows you to read only specific nodes from an [RxDAG]. See [RxDAG::restricted_view].
+///
+/// Unlike [RxDAGSnapshot] this isn't an [RxContext](crate::RxContext), so it can't be used to read other nodes,
+/// which makes it useful to give to e.g. plugins.
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct RestrictedSnapshot<'a, 'c: 'a, A: Allocator + 'c = Global> {
+    snapshot: RxDAGSnapshot<'a, 'c, A>,
+    graph_id: RxDAGUid<'c, A>,
+    allowed: HashSet<usize>
+}
+
+impl<'c, A: Allocator + 'c> RxDAG<'c, A> {
+    /// Returns a view which can only read the nodes in `allowed`. Refs from other graphs are ignored.
+    ///
+    /// Like [RxDAG::stale], newly-set values or computations will not be returned until [RxDAG::recompute] is called.
+    pub fn restricted_view(&self, allowed: &[UntypedRxRef<'c, A>]) -> RestrictedSnapshot<'_, 'c, A> {
+        let allowed = allowed.iter()
+            .filter(|rx| rx.graph_id() == self.id())
+            .map(|rx| rx.index())
+            .collect();
+        RestrictedSnapshot {
+            snapshot: self.stale(),
+            graph_id: self.id(),
+            allowed
+        }
+    }
+}
+
+impl<'a, 'c: 'a, A: Allocator + 'c> RestrictedSnapshot<'a, 'c, A> {
+    /// Whether the node can be read from this view.
+    pub fn is_allowed(&self, rx: UntypedRxRef<'c, A>) -> bool {
+        rx.graph_id() == self.graph_id && self.allowed.contains(&rx.index())
+    }
+
+    /// Read the node if it's allowed, otherwise return `None`.
+    pub fn get<T>(&self, rx: RxRef<'c, T, A>) -> Option<&'a T> {
+        if self.is_allowed(rx.raw()) {
+            rx.try_get(self.snapshot)
+        } else {
+            None
+        }
+    }
+}
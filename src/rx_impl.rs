@@ -1,13 +1,17 @@
 use std::alloc::Allocator;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell, UnsafeCell};
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
+use std::rc::Rc;
+use std::any::type_name;
 use std::mem::{MaybeUninit, size_of, transmute};
 use crate::misc::stable_deref2::{Deref2, StableDeref2};
 use crate::misc::frozen_vec::FrozenSlice;
+use crate::misc::flag_set::FlagSet;
 use crate::misc::assert_variance::assert_is_covariant;
-use crate::dag::{RxInput, RxSubDAG};
+use crate::dag::{RxInput, RxSubDAG, MismatchPolicy};
 use crate::dag_uid::RxDAGUid;
+use crate::changed_inputs::ChangedInputs;
 
 #[derive(Debug)]
 pub(crate) enum RxDAGElem<'c, A: Allocator> {
@@ -27,13 +31,47 @@ pub(crate) type RxEdge<'c, A> = dyn RxEdgeTrait<A> + 'c;
 assert_is_covariant!(for[A] (RxEdge<'c, A>) over 'c);
 
 pub(crate) trait RxTrait<A: Allocator>: Debug {
-    fn post_read(&self) -> bool;
+    /// Whether the node has an up-to-date value. Lazy nodes don't until they're read.
+    fn is_initialized(&self) -> bool;
+    /// If the node is lazy and needs to compute, compute it now.
+    /// `before` must contain the node's edge.
+    // 'c2 must outlive 'c, this is a workaround because there aren't covariant trait lifetime parameters
+    fn ensure_computed<'c2>(&self, _before: FrozenSlice<'_, RxDAGElem<'c2, A>>, _graph_id: RxDAGUid<'c2, A>, _mismatch_policy: MismatchPolicy, _dirty: &Cell<bool>, _read_flags: &FlagSet) {}
+    /// If the node is lazy and computed since the last call, returns the inputs it read (relative to its edge)
+    fn take_discovered_offsets(&self) -> Option<Vec<usize>> {
+        None
+    }
+    /// Mark a demand-driven node as needing to compute when it's next read.
+    fn invalidate(&self) {
+        unreachable!("only demand-driven nodes are invalidated")
+    }
 
-    fn recompute(&mut self);
+    /// Returns whether the node was recomputed
+    fn recompute(&mut self) -> bool;
     fn did_recompute(&self) -> bool;
+    /// How many recomputes changed the node. Unlike [RxTrait::did_recompute] this isn't reset,
+    /// so a computation which didn't run in the recompute where the node changed can still tell.
+    fn num_changes(&self) -> u64;
+    /// Whether the node was set or mutated since the last recompute, so the next recompute will change it
+    fn has_pending(&self) -> bool;
+    /// Discard the value set since the last recompute, if any
+    fn discard_pending(&mut self);
     fn post_recompute(&mut self);
 
-    unsafe fn _get_dyn(&self) -> *const ();
+    /// Name of the value's type, so [CowRxImpl] can check that it's aliasing a value of the same type
+    fn value_type_name(&self) -> &'static str;
+    /// Size of the value's type (not including any data it owns on the heap)
+    fn value_size(&self) -> usize;
+
+    /// The current value. This doesn't mark the node read: the graph tracks reads, see [RxSubDAG::read_flags]
+    unsafe fn _value_ptr_dyn(&self) -> *const ();
+    /// The value before the node was recomputed, if it was recomputed in the current [RxDAG::recompute], otherwise null
+    unsafe fn _previous_dyn(&self) -> *const ();
+    /// The value the node was created with, if it was kept, otherwise null
+    unsafe fn _initial_dyn(&self) -> *const () {
+        std::ptr::null()
+    }
+    unsafe fn _get_mut_dyn(&mut self) -> *mut ();
     unsafe fn _take_latest_dyn(&self, ptr: *mut MaybeUninit<CurrentOrNext<'_, ()>>, size: usize);
     unsafe fn _set_dyn(&self, ptr: *mut MaybeUninit<()>, size: usize);
 }
@@ -41,25 +79,96 @@ pub(crate) trait RxTrait<A: Allocator>: Debug {
 pub(crate) struct RxImpl<T, A: Allocator> {
     current: T,
     next: Cell<Option<T>>,
-    // Rx flags (might have same flags for a group to reduce traversing all Rxs)
-    did_read: Cell<bool>,
+    // Kept until the end of the recompute where `current` was replaced, so side-effects can see both
+    previous: Option<T>,
+    // Rx flags. The DAG tracks which nodes got recomputed, so it only traverses those to reset `did_recompute`
     did_recompute: bool,
+    // Whether `current` was mutated directly, so it should be marked recomputed
+    did_mutate: bool,
+    num_changes: u64,
     phantom: PhantomData<A>
 }
 
+/// Computes a [LazyRxImpl]'s value from its edge's inputs. Also collects the input offsets into the second argument.
+pub(crate) type LazyCompute<'c, T, A> = Rc<RefCell<dyn FnMut(RxInput<'_, 'c, A>, &mut Vec<usize>) -> T + 'c>>;
+
+/// Node which isn't computed until it's read.
+pub(crate) struct LazyRxImpl<'c, T, A: Allocator> {
+    // Uninitialized until first read
+    inner: UnsafeCell<Option<RxImpl<T, A>>>,
+    compute: LazyCompute<'c, T, A>,
+    edge_index: usize,
+    // Set on creation and when invalidated (demand-driven nodes), cleared when computed
+    needs_compute: Cell<bool>,
+    did_invalidate: Cell<bool>,
+    // Inputs read by the last compute on demand, which the edge installs on the next recompute
+    discovered_offsets: Cell<Option<Vec<usize>>>
+}
+
+/// Value of a [CowRxImpl]
+pub(crate) enum CowValue<'c, T, A: Allocator> {
+    Owned(T),
+    /// Points to an input, which is always a node with the same type. Reads go through the node every time,
+    /// so they see its current value even if it's another [CowRxImpl] which switched between owning and aliasing,
+    /// or it changed in a pass which hasn't recomputed this node yet.
+    /// Nodes are in boxes which are never moved, and [RxDAG::compact] never frees a node which is read, so the pointer is always valid.
+    Alias(*const Rx<'c, A>)
+}
+
+/// Node which may alias an input's value instead of storing its own.
+pub(crate) struct CowRxImpl<'c, T, A: Allocator>(RxImpl<CowValue<'c, T, A>, A>);
+
+/// Variable which also keeps the value it was created with.
+pub(crate) struct InitialRxImpl<T, A: Allocator> {
+    inner: RxImpl<T, A>,
+    initial: T
+}
+
 // trait RxEdgeTrait<cov 'c, A: Allocator>: Debug
 pub(crate) trait RxEdgeTrait<A: Allocator>: Debug {
-    // fn recompute(&mut self, index: usize, before: &[RxDAGElem<'c, A>], after: &[RxDAGElem<'c, A>], graph_id: RxDAGUid<'c, A>);
+    // fn recompute(&mut self, index: usize, before: &[RxDAGElem<'c, A>], after: &[RxDAGElem<'c, A>], graph_id: RxDAGUid<'c, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet);
     // 'c2 must outlive 'c, this is a workaround beause there aren't covariant trait lifetime parameters
-    fn recompute<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], after: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>);
+    /// Returns whether the edge was deferred, and needs [RxEdgeTrait::run_deferred]
+    #[allow(clippy::too_many_arguments)]
+    fn recompute<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], after: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) -> bool;
+    fn input_backwards_offsets(&self) -> &[usize];
+    fn num_outputs(&self) -> usize;
+    /// Runs the edge if it's deferred and its inputs changed in the last [RxEdgeTrait::recompute]
+    fn run_deferred<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet);
+    /// Runs the edge now regardless of whether its inputs changed, which rediscovers its inputs.
+    /// Outputs are set and will be committed on the next recompute.
+    #[allow(clippy::too_many_arguments)]
+    fn rediscover<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], after: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet);
+    /// Instead of [RxEdgeTrait::recompute], if the inputs changed, run on the next recompute
+    fn postpone<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>]);
+    fn tag(&self) -> Option<u32>;
+    /// Whether the edge can be postponed, see [RxDAG::run_crx_reading_previous]
+    fn can_postpone(&self) -> bool;
 }
 
+/// Decides whether an edge recomputes from which of its inputs changed. See [RxDAG::new_crx_when]
+type ShouldRecompute<'c> = Box<dyn Fn(&ChangedInputs) -> bool + 'c>;
+
 pub(crate) struct RxEdgeImpl<'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&Rx<'c, A>>) + 'c, A: Allocator> {
     // Takes current of input values (first argument) and sets next of output values (second argument).
     compute: F,
     num_outputs: usize,
     input_backwards_offsets: Vec<usize>,
-    cached_inputs: Vec<*const Rx<'c, A>>
+    cached_inputs: Vec<*const Rx<'c, A>>,
+    // If set, only recomputes if any input changed *and* this returns true
+    should_recompute: Option<ShouldRecompute<'c>>,
+    cached_changed_inputs: ChangedInputs,
+    // If set, instead of computing when inputs change, computes after the entire DAG recomputes
+    is_deferred: bool,
+    did_defer: bool,
+    // Set while computing, so if compute panics the edge will rerun (and rediscover its inputs) on the next recompute
+    force_recompute: bool,
+    // If set, the output is a lazy node which may compute itself and discover new inputs when read
+    is_lazy: bool,
+    // Side-effects with a tag only run in [RxDAG::recompute_tag] with the same tag (and in regular recomputes)
+    tag: Option<u32>,
+    // If set, reads its inputs' previous values, so it can't be postponed to a recompute where they're gone
+    reads_previous: bool
 }
 
 pub(crate) enum CurrentOrNext<'a, T> {
@@ -71,11 +180,15 @@ impl<'c, A: Allocator> RxDAGElem<'c, A> {
     /// Recomputes this one element.
     /// If it's a node, updates the value which gets returned when you call [Var::get] or [CRx::get].
     /// If it's an edge, reruns `compute` if any of its inputs changed.
-    pub(crate) fn recompute(&mut self, index: usize, before: &[RxDAGElem<'c, A>], after: &[RxDAGElem<'c, A>], graph_id: RxDAGUid<'c, A>) {
+    ///
+    /// Returns whether there is more work in [RxDAGElem::post_recompute] or [RxDAGElem::run_deferred]:
+    /// that is, whether this is a node which got recomputed, or an edge which got deferred.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn recompute(&mut self, index: usize, before: &[RxDAGElem<'c, A>], after: &[RxDAGElem<'c, A>], graph_id: RxDAGUid<'c, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) -> bool {
         match self {
             RxDAGElem::Node(x) => x.recompute(),
             // this is ok because this allows an arbitrary lifetime, but we pass 'c which is required
-            RxDAGElem::Edge(x) => x.recompute(index, before, after, graph_id)
+            RxDAGElem::Edge(x) => x.recompute(index, before, after, graph_id, mismatch_policy, dirty, read_flags)
         }
     }
 
@@ -86,22 +199,37 @@ impl<'c, A: Allocator> RxDAGElem<'c, A> {
         }
     }
 
+    /// If this is a deferred edge whose inputs changed, runs it now that the DAG has recomputed.
+    pub(crate) fn run_deferred(&mut self, index: usize, before: &[RxDAGElem<'c, A>], graph_id: RxDAGUid<'c, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) {
+        match self {
+            RxDAGElem::Node(_) => {}
+            RxDAGElem::Edge(x) => x.run_deferred(index, before, graph_id, mismatch_policy, dirty, read_flags)
+        }
+    }
+
+    pub(crate) fn as_edge_mut(&mut self) -> Option<&mut RxEdge<'c, A>> {
+        match self {
+            RxDAGElem::Edge(x) => Some(x.as_mut()),
+            _ => None
+        }
+    }
+
     pub(crate) fn as_node(&self) -> Option<&Rx<'c, A>> {
         match self {
             RxDAGElem::Node(x) => Some(x.as_ref()),
             _ => None
         }
     }
-}
 
-impl<'a, 'c, A: Allocator> RxDAGElemRef<'a, 'c, A> {
-    pub(crate) fn post_read(self) -> bool {
+    pub(crate) fn as_node_mut(&mut self) -> Option<&mut Rx<'c, A>> {
         match self {
-            RxDAGElemRef::Node(node) => node.post_read(),
-            RxDAGElemRef::Edge(_) => false
+            RxDAGElem::Node(x) => Some(x.as_mut()),
+            _ => None
         }
     }
+}
 
+impl<'a, 'c, A: Allocator> RxDAGElemRef<'a, 'c, A> {
     //noinspection RsSelfConvention because this is itself a reference
     pub(crate) fn as_node(self) -> Option<&'a Rx<'c, A>> {
         match self {
@@ -116,21 +244,17 @@ impl<T, A: Allocator> RxImpl<T, A> {
         Self {
             current: init,
             next: Cell::new(None),
-            did_read: Cell::new(false),
+            previous: None,
             did_recompute: false,
+            did_mutate: false,
+            num_changes: 0,
             phantom: PhantomData
         }
     }
 
-    pub(crate) fn get(&self) -> &T {
-        self.did_read.set(true);
-        &self.current
-    }
-
     /// Take `next` if set, otherwise returns a reference to `current`.
     /// The value should then be re-assigned to `next` via `set`.
     pub(crate) fn take_latest(&self) -> CurrentOrNext<'_, T> {
-        self.did_read.set(true);
         match self.next.take() {
             None => CurrentOrNext::Current(&self.current),
             Some(next) => CurrentOrNext::Next(next)
@@ -140,36 +264,86 @@ impl<T, A: Allocator> RxImpl<T, A> {
     pub(crate) fn set(&self, value: T) {
         self.next.set(Some(value));
     }
+
+    /// Get a mutable reference to the latest value, which will be marked recomputed.
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        if let Some(next) = self.next.get_mut().take() {
+            self.current = next;
+        }
+        self.did_mutate = true;
+        &mut self.current
+    }
 }
 
 impl<T, A: Allocator> RxTrait<A> for RxImpl<T, A> {
-    fn post_read(&self) -> bool {
-        self.did_read.take()
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn value_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn value_size(&self) -> usize {
+        size_of::<T>()
     }
 
-    fn recompute(&mut self) {
+    fn recompute(&mut self) -> bool {
         debug_assert!(!self.did_recompute);
         match self.next.take() {
             // Didn't update
             None => {}
             // Did update
             Some(next) => {
-                self.current = next;
+                self.previous = Some(std::mem::replace(&mut self.current, next));
                 self.did_recompute = true;
             }
         }
+        if self.did_mutate {
+            self.did_mutate = false;
+            self.did_recompute = true;
+        }
+        if self.did_recompute {
+            self.num_changes += 1;
+        }
+        self.did_recompute
     }
 
     fn did_recompute(&self) -> bool {
         self.did_recompute
     }
 
+    fn num_changes(&self) -> u64 {
+        self.num_changes
+    }
+
+    fn has_pending(&self) -> bool {
+        // Nothing else references `next` here
+        self.did_mutate || unsafe { (*self.next.as_ptr()).is_some() }
+    }
+
+    fn discard_pending(&mut self) {
+        self.next.get_mut().take();
+    }
+
     fn post_recompute(&mut self) {
         self.did_recompute = false;
+        self.previous = None;
     }
 
-    unsafe fn _get_dyn(&self) -> *const () {
-        self.get() as *const T as *const ()
+    unsafe fn _value_ptr_dyn(&self) -> *const () {
+        &self.current as *const T as *const ()
+    }
+
+    unsafe fn _previous_dyn(&self) -> *const () {
+        match &self.previous {
+            None => std::ptr::null(),
+            Some(previous) => previous as *const T as *const ()
+        }
+    }
+
+    unsafe fn _get_mut_dyn(&mut self) -> *mut () {
+        self.get_mut() as *mut T as *mut ()
     }
 
     unsafe fn _take_latest_dyn(&self, ptr: *mut MaybeUninit<CurrentOrNext<'_, ()>>, size: usize) {
@@ -189,6 +363,294 @@ impl<T, A: Allocator> RxTrait<A> for RxImpl<T, A> {
     }
 }
 
+impl<'c, T, A: Allocator> LazyRxImpl<'c, T, A> {
+    pub(crate) fn new(edge_index: usize, compute: LazyCompute<'c, T, A>) -> Self {
+        Self {
+            inner: UnsafeCell::new(None),
+            compute,
+            edge_index,
+            needs_compute: Cell::new(true),
+            did_invalidate: Cell::new(false),
+            discovered_offsets: Cell::new(None)
+        }
+    }
+
+    fn inner(&self) -> Option<&RxImpl<T, A>> {
+        // The value is only replaced in `ensure_computed` when it needs to compute,
+        // and any reference to it would've computed it
+        unsafe { &*self.inner.get() }.as_ref()
+    }
+
+    fn inner_mut(&mut self) -> Option<&mut RxImpl<T, A>> {
+        self.inner.get_mut().as_mut()
+    }
+
+    fn expect_inner(&self) -> &RxImpl<T, A> {
+        self.inner().expect("lazy node accessed before it was computed")
+    }
+}
+
+impl<'c, T, A: Allocator> RxTrait<A> for LazyRxImpl<'c, T, A> {
+    fn is_initialized(&self) -> bool {
+        !self.needs_compute.get()
+    }
+
+    fn value_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn value_size(&self) -> usize {
+        size_of::<T>()
+    }
+
+    fn ensure_computed<'c2>(&self, before: FrozenSlice<'_, RxDAGElem<'c2, A>>, graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) {
+        // 'c2 must outlive 'c, this is a workaround because there aren't covariant trait lifetime parameters
+        let (before, graph_id) = unsafe {
+            transmute::<(FrozenSlice<'_, RxDAGElem<'c2, A>>, RxDAGUid<'c2, A>), (FrozenSlice<'_, RxDAGElem<'c, A>>, RxDAGUid<'c, A>)>((before, graph_id))
+        };
+        if !self.needs_compute.get() {
+            return;
+        }
+
+        let input = RxInput(RxSubDAG {
+            before: before.truncated(self.edge_index),
+            index: self.edge_index,
+            id: graph_id,
+            mismatch_policy,
+            // Lazy edges have exactly one output
+            num_outputs: 1,
+            dirty,
+            read_flags,
+            stale_reads: None,
+            set_listeners: None
+        });
+        let mut input_backwards_offsets = Vec::new();
+        let value = (self.compute.borrow_mut())(input, &mut input_backwards_offsets);
+        // Nothing references the old value, see `inner`
+        match unsafe { &mut *self.inner.get() } {
+            inner @ None => *inner = Some(RxImpl::new(value)),
+            Some(inner) => inner.current = value
+        }
+        self.needs_compute.set(false);
+        self.discovered_offsets.set(Some(input_backwards_offsets));
+    }
+
+    fn take_discovered_offsets(&self) -> Option<Vec<usize>> {
+        self.discovered_offsets.take()
+    }
+
+    fn invalidate(&self) {
+        self.needs_compute.set(true);
+        self.did_invalidate.set(true);
+    }
+
+    fn recompute(&mut self) -> bool {
+        let did_invalidate = self.did_invalidate.take();
+        match self.inner_mut() {
+            None => false,
+            Some(inner) => {
+                // Invalidating changes the value (when it's next read), so dependents must recompute
+                inner.did_mutate |= did_invalidate;
+                inner.recompute()
+            }
+        }
+    }
+
+    fn did_recompute(&self) -> bool {
+        self.inner().is_some_and(|inner| inner.did_recompute())
+    }
+
+    fn num_changes(&self) -> u64 {
+        self.inner().map_or(0, |inner| inner.num_changes())
+    }
+
+    fn has_pending(&self) -> bool {
+        self.did_invalidate.get() || self.inner().is_some_and(|inner| inner.has_pending())
+    }
+
+    fn discard_pending(&mut self) {
+        if let Some(inner) = self.inner_mut() {
+            inner.discard_pending()
+        }
+    }
+
+    fn post_recompute(&mut self) {
+        if let Some(inner) = self.inner_mut() {
+            inner.post_recompute()
+        }
+    }
+
+    unsafe fn _value_ptr_dyn(&self) -> *const () {
+        self.expect_inner()._value_ptr_dyn()
+    }
+
+    unsafe fn _previous_dyn(&self) -> *const () {
+        self.expect_inner()._previous_dyn()
+    }
+
+    unsafe fn _get_mut_dyn(&mut self) -> *mut () {
+        self.inner_mut().expect("lazy node accessed before it was computed")._get_mut_dyn()
+    }
+
+    unsafe fn _take_latest_dyn(&self, ptr: *mut MaybeUninit<CurrentOrNext<'_, ()>>, size: usize) {
+        self.expect_inner()._take_latest_dyn(ptr, size)
+    }
+
+    unsafe fn _set_dyn(&self, ptr: *mut MaybeUninit<()>, size: usize) {
+        self.expect_inner()._set_dyn(ptr, size)
+    }
+}
+
+impl<'c, T, A: Allocator> CowRxImpl<'c, T, A> {
+    pub(crate) fn new(init: CowValue<'c, T, A>) -> Self {
+        CowRxImpl(RxImpl::new(init))
+    }
+}
+
+impl<'c, T, A: Allocator> CowValue<'c, T, A> {
+    fn as_ptr(&self) -> *const T {
+        match self {
+            CowValue::Owned(value) => value as *const T,
+            // See `Alias`: the node is still in the graph
+            CowValue::Alias(node) => unsafe { (**node)._value_ptr_dyn() as *const T }
+        }
+    }
+}
+
+impl<'c, T, A: Allocator> RxTrait<A> for CowRxImpl<'c, T, A> {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn recompute(&mut self) -> bool {
+        self.0.recompute()
+    }
+
+    fn did_recompute(&self) -> bool {
+        self.0.did_recompute()
+    }
+
+    fn num_changes(&self) -> u64 {
+        self.0.num_changes()
+    }
+
+    fn has_pending(&self) -> bool {
+        self.0.has_pending()
+    }
+
+    fn discard_pending(&mut self) {
+        self.0.discard_pending()
+    }
+
+    fn post_recompute(&mut self) {
+        self.0.post_recompute()
+    }
+
+    fn value_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn value_size(&self) -> usize {
+        size_of::<T>()
+    }
+
+    unsafe fn _value_ptr_dyn(&self) -> *const () {
+        self.0.current.as_ptr() as *const ()
+    }
+
+    unsafe fn _previous_dyn(&self) -> *const () {
+        match &self.0.previous {
+            Some(CowValue::Owned(previous)) => previous as *const T as *const (),
+            // The aliased input's value already changed
+            None | Some(CowValue::Alias(_)) => std::ptr::null()
+        }
+    }
+
+    unsafe fn _get_mut_dyn(&mut self) -> *mut () {
+        unreachable!("computed values can't be mutated")
+    }
+
+    unsafe fn _take_latest_dyn(&self, _ptr: *mut MaybeUninit<CurrentOrNext<'_, ()>>, _size: usize) {
+        unreachable!("computed values can't be modified")
+    }
+
+    unsafe fn _set_dyn(&self, ptr: *mut MaybeUninit<()>, size: usize) {
+        // Set to a `CowValue<T>`
+        self.0._set_dyn(ptr, size)
+    }
+}
+
+impl<T: Clone, A: Allocator> InitialRxImpl<T, A> {
+    pub(crate) fn new(init: T) -> Self {
+        InitialRxImpl {
+            initial: init.clone(),
+            inner: RxImpl::new(init)
+        }
+    }
+}
+
+impl<T, A: Allocator> RxTrait<A> for InitialRxImpl<T, A> {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn recompute(&mut self) -> bool {
+        self.inner.recompute()
+    }
+
+    fn did_recompute(&self) -> bool {
+        self.inner.did_recompute()
+    }
+
+    fn num_changes(&self) -> u64 {
+        self.inner.num_changes()
+    }
+
+    fn has_pending(&self) -> bool {
+        self.inner.has_pending()
+    }
+
+    fn discard_pending(&mut self) {
+        self.inner.discard_pending()
+    }
+
+    fn post_recompute(&mut self) {
+        self.inner.post_recompute()
+    }
+
+    fn value_type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn value_size(&self) -> usize {
+        size_of::<T>() * 2
+    }
+
+    unsafe fn _value_ptr_dyn(&self) -> *const () {
+        self.inner._value_ptr_dyn()
+    }
+
+    unsafe fn _previous_dyn(&self) -> *const () {
+        self.inner._previous_dyn()
+    }
+
+    unsafe fn _initial_dyn(&self) -> *const () {
+        &self.initial as *const T as *const ()
+    }
+
+    unsafe fn _get_mut_dyn(&mut self) -> *mut () {
+        self.inner._get_mut_dyn()
+    }
+
+    unsafe fn _take_latest_dyn(&self, ptr: *mut MaybeUninit<CurrentOrNext<'_, ()>>, size: usize) {
+        self.inner._take_latest_dyn(ptr, size)
+    }
+
+    unsafe fn _set_dyn(&self, ptr: *mut MaybeUninit<()>, size: usize) {
+        self.inner._set_dyn(ptr, size)
+    }
+}
+
 impl<'c, A: Allocator> Deref2 for RxDAGElem<'c, A> {
     type Target<'a> = RxDAGElemRef<'a, 'c, A> where Self: 'a;
 
@@ -209,7 +671,70 @@ impl<'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&R
             input_backwards_offsets,
             num_outputs,
             compute,
-            cached_inputs: Vec::with_capacity(num_inputs)
+            cached_inputs: Vec::with_capacity(num_inputs),
+            should_recompute: None,
+            cached_changed_inputs: ChangedInputs::default(),
+            is_deferred: false,
+            did_defer: false,
+            force_recompute: false,
+            is_lazy: false,
+            tag: None,
+            reads_previous: false
+        }
+    }
+
+    /// The output is a [LazyRxImpl], whose inputs are installed from the node after it computes on demand.
+    pub(crate) fn lazy(mut self) -> Self {
+        debug_assert!(self.num_outputs == 1, "lazy edges have exactly one output");
+        self.is_lazy = true;
+        self
+    }
+
+    /// When inputs change, wait until the entire DAG recomputes and then compute.
+    /// Only side-effects (no outputs) can be deferred.
+    pub(crate) fn deferred(mut self) -> Self {
+        debug_assert!(self.num_outputs == 0, "only side-effects can be deferred");
+        self.is_deferred = true;
+        self
+    }
+
+    /// Compute on the next recompute even if no inputs changed, because they haven't been discovered yet.
+    pub(crate) fn uninit(mut self) -> Self {
+        self.force_recompute = true;
+        self
+    }
+
+    /// Tag the side-effect so it can be run selectively by [RxDAG::recompute_tag].
+    pub(crate) fn tagged(mut self, tag: u32) -> Self {
+        debug_assert!(self.num_outputs == 0, "only side-effects can be tagged");
+        self.tag = Some(tag);
+        self
+    }
+
+    /// The side-effect reads its inputs' previous values, so it always runs in the recompute where they changed.
+    pub(crate) fn reads_previous(mut self) -> Self {
+        debug_assert!(self.num_outputs == 0, "only side-effects are postponed");
+        self.reads_previous = true;
+        self
+    }
+
+    /// Only recompute when `should_recompute` returns true (and any input changed).
+    pub(crate) fn with_should_recompute(mut self, should_recompute: impl Fn(&ChangedInputs) -> bool + 'c) -> Self {
+        self.should_recompute = Some(Box::new(should_recompute));
+        self
+    }
+
+    fn should_recompute(&mut self, index: usize) -> bool {
+        match &self.should_recompute {
+            None => true,
+            Some(should_recompute) => {
+                self.cached_changed_inputs.clear();
+                // Offsets are collected in graph order, so the largest offset is the earliest input
+                for (offset, input) in self.input_backwards_offsets.iter().zip(self.cached_inputs.iter()) {
+                    self.cached_changed_inputs.push(index - offset, unsafe { &**input }.did_recompute());
+                }
+                should_recompute(&self.cached_changed_inputs)
+            }
         }
     }
 
@@ -221,31 +746,111 @@ impl<'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&R
 }
 
 impl<'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&Rx<'c, A>>) + 'c, A: Allocator> RxEdgeTrait<A> for RxEdgeImpl<'c, F, A> {
-    fn recompute<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], after: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>) {
+    fn recompute<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], after: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) -> bool {
         // 'c2 must outlive 'c, this is a workaround because there aren't covariant trait lifetime parameters
         let (before, after, graph_id) = unsafe {
             transmute::<(&[RxDAGElem<'c2, A>], &[RxDAGElem<'c2, A>], RxDAGUid<'c2, A>), (&[RxDAGElem<'c, A>], &[RxDAGElem<'c, A>], RxDAGUid<'c, A>)>((before, after, graph_id))
         };
 
-        debug_assert!(self.cached_inputs.is_empty());
+        if self.is_lazy {
+            let output = after[0].as_node().expect("broken RxDAG: RxEdge output must be a node");
+            if let Some(input_backwards_offsets) = output.take_discovered_offsets() {
+                self.input_backwards_offsets = input_backwards_offsets;
+            }
+        }
+
+        if self.needs_compute(index, before) {
+            if self.is_deferred {
+                self.did_defer = true;
+            } else {
+                self.compute(index, before, after, graph_id, mismatch_policy, dirty, read_flags);
+            }
+        }
+        self.did_defer
+    }
+
+    fn input_backwards_offsets(&self) -> &[usize] {
+        &self.input_backwards_offsets
+    }
+
+    fn num_outputs(&self) -> usize {
+        self.num_outputs
+    }
+
+    fn run_deferred<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) {
+        // 'c2 must outlive 'c, this is a workaround because there aren't covariant trait lifetime parameters
+        let (before, graph_id) = unsafe {
+            transmute::<(&[RxDAGElem<'c2, A>], RxDAGUid<'c2, A>), (&[RxDAGElem<'c, A>], RxDAGUid<'c, A>)>((before, graph_id))
+        };
+
+        if self.did_defer {
+            self.did_defer = false;
+            // Deferred edges have no outputs
+            self.compute(index, before, &[], graph_id, mismatch_policy, dirty, read_flags);
+        }
+    }
+
+    fn rediscover<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>], after: &[RxDAGElem<'c2, A>], graph_id: RxDAGUid<'c2, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) {
+        // 'c2 must outlive 'c, this is a workaround because there aren't covariant trait lifetime parameters
+        let (before, after, graph_id) = unsafe {
+            transmute::<(&[RxDAGElem<'c2, A>], &[RxDAGElem<'c2, A>], RxDAGUid<'c2, A>), (&[RxDAGElem<'c, A>], &[RxDAGElem<'c, A>], RxDAGUid<'c, A>)>((before, after, graph_id))
+        };
+
+        self.compute(index, before, after, graph_id, mismatch_policy, dirty, read_flags);
+    }
+
+    fn postpone<'c2>(&mut self, index: usize, before: &[RxDAGElem<'c2, A>]) {
+        // 'c2 must outlive 'c, this is a workaround because there aren't covariant trait lifetime parameters
+        let before = unsafe { transmute::<&[RxDAGElem<'c2, A>], &[RxDAGElem<'c, A>]>(before) };
+
+        if self.needs_compute(index, before) {
+            self.force_recompute = true;
+        }
+    }
+
+    fn tag(&self) -> Option<u32> {
+        self.tag
+    }
+
+    fn can_postpone(&self) -> bool {
+        !self.reads_previous
+    }
+}
+
+impl<'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&Rx<'c, A>>) + 'c, A: Allocator> RxEdgeImpl<'c, F, A> {
+    /// Whether the edge must compute because its inputs changed (or it must rerun)
+    fn needs_compute(&mut self, index: usize, before: &[RxDAGElem<'c, A>]) -> bool {
+        // Not cleared if the last compute panicked
+        self.cached_inputs.clear();
         self.input_backwards_offsets.iter().copied().map(|offset| {
             before[before.len() - offset].as_node().expect("broken RxDAG: RxEdge input must be a node") as *const Rx<'c, A>
         }).collect_into(&mut self.cached_inputs);
         let mut inputs = self.cached_inputs.iter().map(|x| unsafe { &**x });
 
-        if inputs.any(|x| x.did_recompute()) {
-            // Needs update
-            let mut outputs = self.output_forwards_offsets().map(|offset| {
-                after[offset].as_node().expect("broken RxDAG: RxEdge output must be a node")
-            });
-            let input_dag = RxInput(RxSubDAG {
-                before: FrozenSlice::from(before),
-                index,
-                id: graph_id
-            });
-            (self.compute)(&mut self.input_backwards_offsets, input_dag, &mut outputs);
-        }
+        let result = self.force_recompute || (inputs.any(|x| x.did_recompute()) && self.should_recompute(index));
         self.cached_inputs.clear();
+        result
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn compute(&mut self, index: usize, before: &[RxDAGElem<'c, A>], after: &[RxDAGElem<'c, A>], graph_id: RxDAGUid<'c, A>, mismatch_policy: MismatchPolicy, dirty: &Cell<bool>, read_flags: &FlagSet) {
+        let mut outputs = self.output_forwards_offsets().map(|offset| {
+            after[offset].as_node().expect("broken RxDAG: RxEdge output must be a node")
+        });
+        let input_dag = RxInput(RxSubDAG {
+            before: FrozenSlice::from(before),
+            index,
+            id: graph_id,
+            mismatch_policy,
+            num_outputs: self.num_outputs,
+            dirty,
+            read_flags,
+            stale_reads: None,
+            set_listeners: None
+        });
+        self.force_recompute = true;
+        (self.compute)(&mut self.input_backwards_offsets, input_dag, &mut outputs);
+        self.force_recompute = false;
     }
 }
 
@@ -258,7 +863,22 @@ impl<'c, A: Allocator> dyn RxTrait<A> + 'c {
 
     pub(crate) unsafe fn get_dyn<T>(&self) -> &T {
         debug_assert_eq!(size_of::<*const T>(), size_of::<*const ()>(), "won't work");
-        &*(self._get_dyn() as *const T)
+        &*(self._value_ptr_dyn() as *const T)
+    }
+
+    pub(crate) unsafe fn initial_dyn<T>(&self) -> Option<&T> {
+        debug_assert_eq!(size_of::<*const T>(), size_of::<*const ()>(), "won't work");
+        (self._initial_dyn() as *const T).as_ref()
+    }
+
+    pub(crate) unsafe fn previous_dyn<T>(&self) -> Option<&T> {
+        debug_assert_eq!(size_of::<*const T>(), size_of::<*const ()>(), "won't work");
+        (self._previous_dyn() as *const T).as_ref()
+    }
+
+    pub(crate) unsafe fn get_mut_dyn<T>(&mut self) -> &mut T {
+        debug_assert_eq!(size_of::<*const T>(), size_of::<*const ()>(), "won't work");
+        &mut *(self._get_mut_dyn() as *mut T)
     }
 
     pub(crate) unsafe fn take_latest_dyn<T>(&self) -> CurrentOrNext<'_, T> {
@@ -273,8 +893,35 @@ impl<T, A: Allocator> Debug for RxImpl<T, A> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RxImpl")
             .field("next.is_some()", &unsafe { &*self.next.as_ptr() }.is_some())
-            .field("did_read", &self.did_read.get())
             .field("did_recompute", &self.did_recompute)
+            .field("did_mutate", &self.did_mutate)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'c, T, A: Allocator> Debug for CowRxImpl<'c, T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CowRxImpl")
+            .field(&self.0)
+            .field(&matches!(self.0.current, CowValue::Alias(_)))
+            .finish()
+    }
+}
+
+impl<T, A: Allocator> Debug for InitialRxImpl<T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("InitialRxImpl")
+            .field(&self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'c, T, A: Allocator> Debug for LazyRxImpl<'c, T, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyRxImpl")
+            .field("inner", &self.inner())
+            .field("edge_index", &self.edge_index)
+            .field("needs_compute", &self.needs_compute.get())
             .finish_non_exhaustive()
     }
 }
@@ -284,6 +931,7 @@ impl<'c, F: FnMut(&mut Vec<usize>, RxInput<'_, 'c, A>, &mut dyn Iterator<Item=&R
         f.debug_struct("RxEdgeImpl")
             .field("num_outputs", &self.num_outputs)
             .field("input_backwards_offsets", &self.input_backwards_offsets)
+            .field("tag", &self.tag)
             .finish_non_exhaustive()
     }
 }
@@ -295,4 +943,12 @@ impl<'a, T> AsRef<T> for CurrentOrNext<'a, T> {
             CurrentOrNext::Next(x) => x
         }
     }
-}
\ No newline at end of file
+}
+impl<'a, T: Clone> CurrentOrNext<'a, T> {
+    pub(crate) fn into_owned(self) -> T {
+        match self {
+            CurrentOrNext::Current(x) => x.clone(),
+            CurrentOrNext::Next(x) => x
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use std::alloc::{Allocator, Global};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::Debug;
+use std::hash::Hash;
 use std::marker::PhantomData;
 use derivative::Derivative;
-use crate::dag::{RxDAG, RxContext, MutRxContext};
+use crate::dag::{RxDAG, RxDAGSnapshot, RxContext, MutRxContext, MismatchPolicy, RxInput};
 use crate::dag_uid::RxDAGUid;
-use crate::clone_set_fn::CloneSetFn;
-use crate::rx_impl::Rx;
+use crate::clone_set_fn::{CloneSetFn, CowSetFn};
+use crate::rx_impl::{Rx, CurrentOrNext};
 use crate::RxSubDAG;
 
 /// Index into the DAG which will give you a node, which may be a variable or computed value.
@@ -60,19 +63,36 @@ pub struct Var<'c, T, A: Allocator = Global>(RxRef<'c, T, A>);
 #[derivative(Clone(bound = ""), Copy(bound = ""))]
 pub struct CRx<'c, T, A: Allocator = Global>(RxRef<'c, T, A>);
 
+/// Computed values which are all outputs of one computation, e.g. from [RxDAG::new_crx_vec].
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = ""))]
+pub struct CRxGroup<'c, T, A: Allocator = Global> {
+    members: Vec<CRx<'c, T, A>>
+}
+
 /// View and mutate a part of a [Var].
 #[derive(Debug)]
 pub struct DVar<'c, S, T, GetFn: Fn(&S) -> &T, SetFn: Fn(&S, T) -> S, A: Allocator = Global> {
-    source: RxRef<'c, S, A>,
-    get: GetFn,
-    set: SetFn
+    pub(crate) source: RxRef<'c, S, A>,
+    pub(crate) get: GetFn,
+    pub(crate) set: SetFn
 }
 
 /// View a part of a [CRx].
 #[derive(Debug)]
 pub struct DCRx<'c, S, T, GetFn: Fn(&S) -> &T, A: Allocator = Global> {
-    source: RxRef<'c, S, A>,
-    get: GetFn
+    pub(crate) source: RxRef<'c, S, A>,
+    pub(crate) get: GetFn
+}
+
+/// View and mutate one entry of a [Var] containing a [HashMap], from [Var::entry].
+///
+/// Unlike [DVar] this reads `Option<&V>`, since the map doesn't store an `Option<V>` to reference.
+#[derive(Debug, Derivative)]
+#[derivative(Clone(bound = "K: Clone"), Copy(bound = "K: Copy"))]
+pub struct DVarEntry<'c, K, V, A: Allocator = Global> {
+    pub(crate) source: RxRef<'c, HashMap<K, V>, A>,
+    pub(crate) key: K
 }
 
 /// [DVar] where the getter and setter are static.
@@ -81,21 +101,84 @@ pub type SDVar<'c, S, T, A = Global> = DVar<'c, S, T, fn(&S) -> &T, fn(&S, T) ->
 /// [DCRx] where the getter is static.
 pub type SDCRx<'c, S, T, A = Global> = DCRx<'c, S, T, fn(&S) -> &T, A>;
 
+/// Callback for [CRx::for_each_subscriber].
+pub type Subscriber<'c, T> = Box<dyn FnMut(&T) + 'c>;
+
 impl<'c, A: Allocator> UntypedRxRef<'c, A> {
-    fn new(graph: &RxDAG<'c, A>, index: usize) -> Self {
+    pub(crate) fn new(graph: &RxDAG<'c, A>, index: usize) -> Self {
         UntypedRxRef {
             index,
             graph_id: graph.id(),
         }
     }
 
+    pub(crate) fn from_parts(index: usize, graph_id: RxDAGUid<'c, A>) -> Self {
+        UntypedRxRef { index, graph_id }
+    }
+
+    /// Whether both refs point to the same node in the same graph.
+    pub fn same_node(self, other: Self) -> bool {
+        self.index == other.index && self.graph_id == other.graph_id
+    }
+
+    pub(crate) fn index(self) -> usize {
+        self.index
+    }
+
+    pub(crate) fn graph_id(self) -> RxDAGUid<'c, A> {
+        self.graph_id
+    }
+
     /// Get the underlying [Rx] where the data is stored.
-    fn get_rx<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> &'a Rx<'c, A> where 'c: 'a {
-        debug_assert!(self.graph_id == graph.id, "RxRef::get_rx: different graph");
+    pub(crate) fn get_rx<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> &'a Rx<'c, A> where 'c: 'a {
+        if self.graph_id != graph.id {
+            graph.mismatch_policy.on_unskippable_mismatch("RxRef::get_rx");
+        }
+        self.get_rx_unchecked(graph)
+    }
+
+    /// Get the underlying [Rx] to write to, or `None` if it's from a different graph and the [MismatchPolicy] skips the write.
+    pub(crate) fn write_rx<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> Option<&'a Rx<'c, A>> where 'c: 'a {
+        if self.graph_id != graph.id && !graph.mismatch_policy.on_mismatch("RxRef::write_rx") {
+            return None;
+        }
+        Some(self.get_rx_unchecked(graph))
+    }
+
+    /// Get the underlying [Rx] without checking the graph
+    fn get_rx_unchecked<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> &'a Rx<'c, A> where 'c: 'a {
         debug_assert!(self.index < graph.before.len(), "RxRef refers to a future node (not a DAG?)");
         // Since we already checked the index, we can use get_unchecked
         let elem = unsafe { graph.before.get_unchecked(self.index) };
-        elem.as_node().expect("RxRef is corrupt: it points to an edge")
+        let rx = elem.as_node().expect("RxRef is corrupt: it points to an edge");
+        if let Some(stale_reads) = graph.stale_reads {
+            stale_reads.set(stale_reads.get() + 1);
+            log::warn!("reading stale value of node {} while graph is dirty", self.index);
+        }
+        rx.ensure_computed(graph.before, graph.id, graph.mismatch_policy, graph.dirty, graph.read_flags);
+        rx
+    }
+
+    /// Get the underlying [Rx] and mark it read, so it's a dependency of the computation reading it.
+    pub(crate) fn read_rx<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> &'a Rx<'c, A> where 'c: 'a {
+        let rx = self.get_rx(graph);
+        graph.read_flags.insert(self.index);
+        rx
+    }
+
+    /// Get the underlying [Rx] if it's in the graph (slice) and initialized, otherwise `None`.
+    fn try_get_rx<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> Option<&'a Rx<'c, A>> where 'c: 'a {
+        if self.graph_id != graph.id {
+            if graph.mismatch_policy == MismatchPolicy::Log {
+                log::error!("RxRef::try_get_rx: different graph");
+            }
+            return None;
+        }
+        if self.index >= graph.before.len() {
+            return None;
+        }
+        let elem = unsafe { graph.before.get_unchecked(self.index) };
+        elem.as_node().filter(|rx| rx.is_initialized())
     }
 }
 
@@ -117,14 +200,82 @@ impl<'c, T, A: Allocator + 'c> RxRef<'c, T, A> {
     }
 
 
+    /// Whether both refs point to the same node in the same graph.
+    pub fn same_node(self, other: Self) -> bool {
+        self.0.same_node(other.0)
+    }
+
     /// Read the node. You can do this on both [Var] and [CRx].
     pub fn get<'a>(self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
-        unsafe { self.0.get_rx(c.sub_dag()).get_dyn() }
+        unsafe { self.0.read_rx(c.sub_dag()).get_dyn() }
+    }
+
+    /// Read the node if it's in the context and initialized, otherwise return `None`.
+    /// Unlike [RxRef::get] this never panics, even if the node is from a different graph
+    /// or isn't visible from the context (e.g. it was created after the computed value reading it).
+    pub fn try_get<'a>(self, c: impl RxContext<'a, 'c, A>) -> Option<&'a T> where 'c: 'a {
+        let graph = c.sub_dag();
+        let rx = self.0.try_get_rx(graph)?;
+        graph.read_flags.insert(self.0.index);
+        Some(unsafe { rx.get_dyn() })
+    }
+
+    /// Read the node if it's in the context and initialized, otherwise return `default`.
+    /// See [RxRef::try_get].
+    pub fn get_or<'a>(self, c: impl RxContext<'a, 'c, A>, default: &'a T) -> &'a T where 'c: 'a {
+        self.try_get(c).unwrap_or(default)
+    }
+
+    /// Get a raw pointer to the node's current value. You can do this on both [Var] and [CRx].
+    ///
+    /// Values are boxed so the pointer is stable even when nodes are added to the DAG,
+    /// which makes it useful for zero-copy FFI. However, it is invalidated by [RxDAG::recompute]
+    /// if the node changes (the old value is dropped and the new one moved in),
+    /// and by dropping the DAG. Don't dereference it while the DAG is being recomputed.
+    pub fn as_ptr<'a>(self, c: impl RxContext<'a, 'c, A>) -> *const T where 'c: 'a {
+        unsafe { self.0.read_rx(c.sub_dag())._value_ptr_dyn() as *const T }
+    }
+
+    /// Read the node without marking it read, so it's not a dependency of the computation reading it.
+    pub(crate) fn get_untracked<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> &'a T where 'c: 'a {
+        unsafe { self.0.get_rx(graph).get_dyn() }
+    }
+
+    /// Read the value the node had before it was recomputed, if it was recomputed in the current [RxDAG::recompute].
+    pub(crate) fn previous<'a>(self, c: impl RxContext<'a, 'c, A>) -> Option<&'a T> where 'c: 'a {
+        unsafe { self.0.get_rx(c.sub_dag()).previous_dyn() }
+    }
+
+    /// How many recomputes changed the node, so a computation can tell whether it changed since the computation last ran,
+    /// even if the computation didn't run when it changed (e.g. it was postponed by [RxDAG::evaluate]).
+    pub(crate) fn num_changes<'a>(self, graph: RxSubDAG<'a, 'c, A>) -> u64 where 'c: 'a {
+        self.0.get_rx(graph).num_changes()
     }
 
     /// Write a new value to the node. The changes will be applied on recompute.
     fn set<'a>(self, c: impl MutRxContext<'a, 'c, A>, value: T) where 'c: 'a {
-        unsafe { self.0.get_rx(c.sub_dag()).set_dyn(value); }
+        let graph = c.sub_dag();
+        let Some(rx) = self.0.write_rx(graph) else { return };
+        graph.notify_set(self.0, &value);
+        unsafe { rx.set_dyn(value); }
+        graph.dirty.set(true);
+    }
+
+    /// Write a new value to the node from inside a computation. The changes will be applied on the next recompute.
+    pub(crate) fn set_in<'a>(self, graph: RxSubDAG<'a, 'c, A>, value: T) where 'c: 'a {
+        let Some(rx) = self.0.write_rx(graph) else { return };
+        unsafe { rx.set_dyn(value); }
+        graph.dirty.set(true);
+    }
+
+    /// Read the value the node was created with, if it was kept.
+    fn initial<'a>(self, c: impl RxContext<'a, 'c, A>) -> Option<&'a T> where 'c: 'a {
+        unsafe { self.0.get_rx(c.sub_dag()).initial_dyn() }
+    }
+
+    /// Take the latest value: the pending value if set (which is removed), otherwise a clone of the current value.
+    pub(crate) fn take_latest<'a>(self, c: impl MutRxContext<'a, 'c, A>) -> T where 'c: 'a, T: Clone {
+        unsafe { self.0.read_rx(c.sub_dag()).take_latest_dyn::<T>() }.into_owned()
     }
 
     /// Apply a transformation to the latest value. If `set` this will apply to the recently-set value.
@@ -133,11 +284,65 @@ impl<'c, T, A: Allocator + 'c> RxRef<'c, T, A> {
     ///
     /// Like `set` the changes only actually reflect in [RxRef::get] on recompute.
     fn modify<'a, F: FnOnce(&T) -> T>(self, c: impl MutRxContext<'a, 'c, A>, modify: F) where 'c: 'a {
-        let rx = self.0.get_rx(c.sub_dag());
+        let graph = c.sub_dag();
+        let Some(rx) = self.0.write_rx(graph) else { return };
+        graph.read_flags.insert(self.0.index);
 
         let latest = unsafe { rx.take_latest_dyn() };
         let next = modify(latest.as_ref());
+        graph.notify_set(self.0, &next);
         unsafe { rx.set_dyn(next); }
+        graph.dirty.set(true);
+    }
+
+    /// Like [RxRef::modify], but if `modify` returns [Cow::Borrowed] nothing is set, so the node doesn't change.
+    pub(crate) fn modify_cow<'a, F: FnOnce(&T) -> Cow<'_, T>>(self, c: impl MutRxContext<'a, 'c, A>, modify: F) where 'c: 'a, T: Clone {
+        let graph = c.sub_dag();
+        let Some(rx) = self.0.write_rx(graph) else { return };
+        graph.read_flags.insert(self.0.index);
+        let notify = |next: &T| graph.notify_set(self.0, next);
+
+        match unsafe { rx.take_latest_dyn::<T>() } {
+            CurrentOrNext::Current(current) => match modify(current) {
+                Cow::Borrowed(_) => {}
+                Cow::Owned(next) => {
+                    notify(&next);
+                    unsafe { rx.set_dyn(next); }
+                    graph.dirty.set(true);
+                }
+            },
+            CurrentOrNext::Next(latest) => {
+                let next = match modify(&latest) {
+                    Cow::Borrowed(_) => None,
+                    Cow::Owned(next) => {
+                        notify(&next);
+                        graph.dirty.set(true);
+                        Some(next)
+                    }
+                };
+                // Put back the pending value if unchanged
+                unsafe { rx.set_dyn(next.unwrap_or(latest)); }
+            }
+        }
+    }
+
+    /// Replace the pending value with the result of `inspect`, which gets the pending value if set.
+    /// If `inspect` returns `None` the pending value is cleared.
+    fn inspect_pending<'a, F: FnOnce(Option<&T>) -> Option<T>>(self, c: impl MutRxContext<'a, 'c, A>, inspect: F) where 'c: 'a {
+        let graph = c.sub_dag();
+        let Some(rx) = self.0.write_rx(graph) else { return };
+        graph.read_flags.insert(self.0.index);
+
+        // Taking the latest value removes the pending value, so we only have to put back a new one
+        let next = match unsafe { rx.take_latest_dyn::<T>() } {
+            CurrentOrNext::Current(_) => inspect(None),
+            CurrentOrNext::Next(pending) => inspect(Some(&pending))
+        };
+        if let Some(next) = next {
+            graph.notify_set(self.0, &next);
+            unsafe { rx.set_dyn(next); }
+            graph.dirty.set(true);
+        }
     }
 }
 
@@ -157,16 +362,36 @@ impl<'c, T, A: Allocator + 'c> Var<'c, T, A> {
         self.0
     }
 
+    /// Whether both refs point to the same variable.
+    pub fn same_node(self, other: Self) -> bool {
+        self.0.same_node(other.0)
+    }
+
     /// Read the variable
     pub fn get<'a>(self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
         self.0.get(c)
     }
 
+    /// Get a raw pointer to the variable's current value.
+    ///
+    /// See [RxRef::as_ptr] for when the pointer is invalidated.
+    pub fn as_ptr<'a>(self, c: impl RxContext<'a, 'c, A>) -> *const T where 'c: 'a {
+        self.0.as_ptr(c)
+    }
+
     /// Write a new value to the variable. The changes will be applied on recompute.
     pub fn set<'a>(self, c: impl MutRxContext<'a, 'c, A>, value: T) where 'c: 'a {
         self.0.set(c, value);
     }
 
+    /// Read the value the variable was created with, if it was created by [RxDAG::new_var_with_initial].
+    /// Otherwise returns `None`.
+    ///
+    /// Unlike [Var::get] this isn't a dependency, since it never changes.
+    pub fn initial<'a>(self, c: impl RxContext<'a, 'c, A>) -> Option<&'a T> where 'c: 'a {
+        self.0.initial(c)
+    }
+
     /// Apply a transformation to the latest value. If [Var::set] this will apply to the recently-set value.
     /// This must be used instead of chaining [Var::set] and [Var::get], since setting a value doesn't make it
     /// returned by [Var::get] until the graph is recomputed.
@@ -176,6 +401,13 @@ impl<'c, T, A: Allocator + 'c> Var<'c, T, A> {
         self.0.modify(c, modify)
     }
 
+    /// Inspect the value set since the last recompute, if any, and replace it with the value `inspect` returns.
+    /// If `inspect` returns `None` the pending value is cancelled, so the variable won't change on recompute
+    /// (unless it's set again).
+    pub fn inspect_pending<'a, F: FnOnce(Option<&T>) -> Option<T>>(self, c: impl MutRxContext<'a, 'c, A>, inspect: F) where 'c: 'a {
+        self.0.inspect_pending(c, inspect)
+    }
+
     /// Create a view of part of the variable.
     ///
     /// Do know that `SetFn` will take the most recently-set value even if the graph hasn't been recomputed.
@@ -197,6 +429,15 @@ impl<'c, T, A: Allocator + 'c> Var<'c, T, A> {
     pub fn derive_using_clone<U, GetFn: Fn(&T) -> &U, SetFn: Fn(&mut T, U)>(self, get: GetFn, set: SetFn) -> DVar<'c, T, U, GetFn, CloneSetFn<T, U, SetFn>, A> where T: Clone {
         self.derive(get, CloneSetFn::new(set))
     }
+
+    /// Create a view of part of the variable, where `set` gets the value as a [Cow] and only clones it
+    /// (via [Cow::to_mut]) if it needs to change.
+    ///
+    /// [DVar::set_if_changed] then doesn't clone or change the variable if `set` doesn't change anything.
+    /// [DVar::set] still clones and sets the variable.
+    pub fn derive_using_cow<U, GetFn: Fn(&T) -> &U, SetFn: Fn(&mut Cow<'_, T>, U)>(self, get: GetFn, set: SetFn) -> DVar<'c, T, U, GetFn, CowSetFn<T, U, SetFn>, A> where T: Clone {
+        self.derive(get, CowSetFn::new(set))
+    }
 }
 
 impl<'c, T, A: Allocator + 'c> CRx<'c, T, A> {
@@ -215,11 +456,35 @@ impl<'c, T, A: Allocator + 'c> CRx<'c, T, A> {
         self.0
     }
 
+    /// Whether both refs point to the same computed value.
+    pub fn same_node(self, other: Self) -> bool {
+        self.0.same_node(other.0)
+    }
+
     /// Read the computed value
     pub fn get<'a>(self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a {
         self.0.get(c)
     }
 
+    /// Read the computed value if it's in the context and initialized, otherwise return `None`.
+    /// See [RxRef::try_get].
+    pub fn try_get<'a>(self, c: impl RxContext<'a, 'c, A>) -> Option<&'a T> where 'c: 'a {
+        self.0.try_get(c)
+    }
+
+    /// Read the computed value if it's in the context and initialized, otherwise return `default`.
+    /// See [RxRef::try_get].
+    pub fn get_or<'a>(self, c: impl RxContext<'a, 'c, A>, default: &'a T) -> &'a T where 'c: 'a {
+        self.0.get_or(c, default)
+    }
+
+    /// Get a raw pointer to the computed value.
+    ///
+    /// See [RxRef::as_ptr] for when the pointer is invalidated.
+    pub fn as_ptr<'a>(self, c: impl RxContext<'a, 'c, A>) -> *const T where 'c: 'a {
+        self.0.as_ptr(c)
+    }
+
     /// Create a view of part of the computed value.
     pub fn derive<U, GetFn: Fn(&T) -> &U>(self, get: GetFn) -> DCRx<'c, T, U, GetFn, A> {
         DCRx {
@@ -227,6 +492,113 @@ impl<'c, T, A: Allocator + 'c> CRx<'c, T, A> {
             get
         }
     }
+
+    /// Run `f` with the old and new value whenever the computed value changes.
+    ///
+    /// Unlike [RxDAG::run_crx], `f` doesn't run on creation, since there's no old value.
+    /// It also doesn't run when a computed value from [RxDAG::new_crx_cow] changes while aliasing its input,
+    /// since the old value is gone.
+    pub fn on_transition(self, g: &RxDAG<'c, A>, mut f: impl FnMut(&T, &T) + 'c) where T: 'c, A: Clone {
+        g.run_crx_reading_previous(move |g| {
+            let new = self.get(g);
+            if let Some(old) = self.0.previous(g) {
+                f(old, new);
+            }
+        });
+    }
+
+    /// Computed value which is `true` for exactly one recompute when `pred` goes from `false` to `true`, otherwise `false`.
+    ///
+    /// The predicate's state on creation isn't a transition, so the value starts `false`.
+    pub fn rising_edge(self, g: &RxDAG<'c, A>, pred: impl Fn(&T) -> bool + 'c) -> CRx<'c, bool, A> where T: 'c, A: Clone {
+        // Set on a rising edge so that we recompute (and reset) on the next recompute
+        let reset = g.new_var(());
+        let mut was_true = None;
+        g.new_crx(move |g| {
+            reset.get(g);
+            let is_true = pred(self.get(g));
+            let is_rising = was_true == Some(false) && is_true;
+            was_true = Some(is_true);
+            if is_rising {
+                reset.raw().set_in(g.0, ());
+            }
+            is_rising
+        })
+    }
+
+    /// Returns a clone of the computed value if it changed at or after revision `since` (see [RxDAG::revision]),
+    /// and advances `since` past the current revision. Otherwise returns `None`.
+    ///
+    /// This lets you poll many values and only clone the ones which changed. Start with `since = 0`
+    /// to get the value on the first poll, and use a separate token for each value.
+    pub fn poll_changed<'a>(self, c: RxDAGSnapshot<'a, 'c, A>, since: &mut u64) -> Option<T> where 'c: 'a, T: Clone {
+        let graph = c.graph();
+        if graph.last_change(self.0.raw().index()) < *since {
+            return None;
+        }
+        *since = graph.revision() + 1;
+        Some(self.get(c).clone())
+    }
+
+    /// Run every subscriber with the computed value now and whenever it changes.
+    ///
+    /// This creates one side-effect for all subscribers, so it's cheaper than calling [RxDAG::run_crx] for each.
+    pub fn for_each_subscriber(self, g: &RxDAG<'c, A>, mut subscribers: Vec<Subscriber<'c, T>>) where T: 'c, A: Clone {
+        g.run_crx(move |g| {
+            let value = self.get(g);
+            for subscriber in subscribers.iter_mut() {
+                subscriber(value);
+            }
+        });
+    }
+}
+
+impl<'c, T: Clone, A: Allocator + 'c> Var<'c, Vec<T>, A> {
+    /// Create a view of each element in `0..len`, so that each can be treated as its own variable.
+    ///
+    /// Setting an element clones the vector (see [Var::derive_using_clone]).
+    /// Like other `derive`s, you can set multiple elements before recompute.
+    // The getter and setter capture the index, so their types can't be named in an alias
+    #[allow(clippy::type_complexity)]
+    pub fn split_indices(self, len: usize) -> Vec<DVar<'c, Vec<T>, T, impl Fn(&Vec<T>) -> &T, impl Fn(&Vec<T>, T) -> Vec<T>, A>> {
+        (0..len).map(|index| self.derive_using_clone(index_get_fn(index), index_set_fn(index))).collect()
+    }
+}
+
+impl<'c, K: Eq + Hash + Clone, V: Clone, A: Allocator + 'c> Var<'c, HashMap<K, V>, A> {
+    /// Create a view of the entry at `key`, so it can be treated as its own variable.
+    /// Setting it to `None` removes the entry.
+    ///
+    /// Setting the entry clones the map. Like other `derive`s, you can set multiple entries before recompute.
+    pub fn entry(self, key: K) -> DVarEntry<'c, K, V, A> {
+        DVarEntry { source: self.0, key }
+    }
+}
+
+fn index_get_fn<T>(index: usize) -> impl Fn(&Vec<T>) -> &T {
+    move |vec| &vec[index]
+}
+
+fn index_set_fn<T>(index: usize) -> impl Fn(&mut Vec<T>, T) {
+    move |vec, value| vec[index] = value
+}
+
+impl<'c, T: Clone + 'c, E: Clone + 'c, A: Allocator + Clone + 'c> CRx<'c, Result<T, E>, A> {
+    /// Split into a computed value which is the `Ok` value, and a computed value which is the `Err` value.
+    /// When the result is `Ok` the error is `None`, and vice versa.
+    pub fn split_result(self, g: &RxDAG<'c, A>) -> (CRx<'c, Option<T>, A>, CRx<'c, Option<E>, A>) {
+        g.new_crx2(move |g| match self.get(g) {
+            Ok(value) => (Some(value.clone()), None),
+            Err(error) => (None, Some(error.clone()))
+        })
+    }
+}
+
+impl<'c, T, A: Allocator + 'c> CRx<'c, Vec<T>, A> {
+    /// Read the computed vector and iterate its elements.
+    pub fn iter_items<'a>(self, c: impl RxContext<'a, 'c, A>) -> std::slice::Iter<'a, T> where 'c: 'a {
+        self.get(c).iter()
+    }
 }
 
 impl<'c, S, T, GetFn: Fn(&S) -> &T, SetFn: Fn(&S, T) -> S, A: Allocator + 'c> DVar<'c, S, T, GetFn, SetFn, A> {
@@ -247,6 +619,44 @@ impl<'c, S, T, GetFn: Fn(&S) -> &T, SetFn: Fn(&S, T) -> S, A: Allocator + 'c> DV
     }
 }
 
+impl<'c, S: Clone, T, GetFn: Fn(&S) -> &T, SetFn: Fn(&mut Cow<'_, S>, T), A: Allocator + 'c> DVar<'c, S, T, GetFn, CowSetFn<S, T, SetFn>, A> {
+    /// Write a new value to the part of the variable this view gets, unless the setter leaves the variable unchanged.
+    /// In that case the variable isn't cloned, and it doesn't change (so dependents don't recompute).
+    pub fn set_if_changed<'a>(&self, c: impl MutRxContext<'a, 'c, A>, value: T) where 'c: 'a, S: 'a {
+        self.source.modify_cow(c, move |latest| {
+            let mut latest = Cow::Borrowed(latest);
+            self.set.set_cow(&mut latest, value);
+            latest
+        })
+    }
+}
+
+impl<'c, K: Eq + Hash + Clone, V: Clone, A: Allocator + 'c> DVarEntry<'c, K, V, A> {
+    /// The key of the entry this view gets.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Read the entry's value, or `None` if the map doesn't contain the key.
+    pub fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> Option<&'a V> where 'c: 'a, K: 'a, V: 'a {
+        self.source.get(c).get(&self.key)
+    }
+
+    /// Insert a new value for the entry, or remove it if `value` is `None`.
+    ///
+    /// Like [DVar::set] this uses the most recently-set map even if the graph hasn't been recomputed.
+    pub fn set<'a>(&self, c: impl MutRxContext<'a, 'c, A>, value: Option<V>) where 'c: 'a, K: 'a, V: 'a {
+        self.source.modify(c, move |old_map| {
+            let mut map = old_map.clone();
+            match value {
+                None => map.remove(&self.key),
+                Some(value) => map.insert(self.key.clone(), value)
+            };
+            map
+        })
+    }
+}
+
 impl<'c, S, T, GetFn: Fn(&S) -> &T, A: Allocator + 'c> DCRx<'c, S, T, GetFn, A> {
     /// Read the part of the computed value this view gets.
     pub fn get<'a>(&self, c: impl RxContext<'a, 'c, A>) -> &'a T where 'c: 'a, S: 'a {
@@ -254,3 +664,25 @@ impl<'c, S, T, GetFn: Fn(&S) -> &T, A: Allocator + 'c> DCRx<'c, S, T, GetFn, A>
     }
 }
 
+impl<'c, T, A: Allocator + 'c> CRxGroup<'c, T, A> {
+    pub(crate) fn new(members: Vec<CRx<'c, T, A>>) -> Self {
+        CRxGroup { members }
+    }
+
+    /// The computed values in this group
+    pub fn members(&self) -> &[CRx<'c, T, A>] {
+        &self.members
+    }
+
+    /// Read every computed value in this group
+    pub fn get_all<'a>(&self, c: impl RxContext<'a, 'c, A>) -> Vec<&'a T> where 'c: 'a {
+        let graph = c.sub_dag();
+        self.members.iter().map(|member| member.get(RxInput(graph))).collect()
+    }
+}
+
+impl<'c, T, A: Allocator + 'c, const N: usize> From<[CRx<'c, T, A>; N]> for CRxGroup<'c, T, A> {
+    fn from(members: [CRx<'c, T, A>; N]) -> Self {
+        CRxGroup::new(members.into())
+    }
+}
@@ -0,0 +1,160 @@
+use std::alloc::{Allocator, Global};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::dag::RxDAG;
+use crate::rx_impl::RxDAGElemRef;
+use crate::rx_ref::UntypedRxRef;
+
+/// The structure of an [RxDAG]: which elements are variables, computed values, and edges,
+/// and how the edges are connected. Doesn't include any values or closures.
+///
+/// Useful for checking that two independently-built graphs have the same topology.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GraphShape(pub Vec<ElemShape>);
+
+/// The shape of a single element in an [RxDAG]. See [GraphShape].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ElemShape {
+    /// A variable (or any node which isn't an edge output)
+    Var,
+    /// A computed value, which is an output of the closest preceding edge
+    CRx,
+    /// An edge, with the offsets of its inputs relative to itself (in reverse),
+    /// and the number of computed values it outputs, which immediately follow it
+    Edge {
+        input_backwards_offsets: Vec<usize>,
+        num_outputs: usize
+    }
+}
+
+/// Returned by [RxDAG::from_shape] when the built graph has a different shape
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeMismatch {
+    pub expected: GraphShape,
+    pub actual: GraphShape
+}
+
+impl<'c> RxDAG<'c> {
+    /// Create a DAG, then build it using `build`, and check that it has the given shape.
+    ///
+    /// Closures can't be serialized, so you must re-supply them in `build`.
+    /// They're supplied by building the graph instead of as a list of closures matching the edges,
+    /// because each closure outputs different types, and variables need their initial values.
+    /// Returns the DAG along with the result of `build` (e.g. refs to its nodes), or [ShapeMismatch] if the shape is different.
+    pub fn from_shape<R>(shape: &GraphShape, build: impl FnOnce(&Self) -> R) -> Result<(Self, R), ShapeMismatch> {
+        Self::from_shape_in(shape, Global, build)
+    }
+}
+
+impl<'c, A: Allocator + Clone + 'c> RxDAG<'c, A> {
+    /// [RxDAG::from_shape] in the specified allocator.
+    pub fn from_shape_in<R>(shape: &GraphShape, alloc: A, build: impl FnOnce(&Self) -> R) -> Result<(Self, R), ShapeMismatch> {
+        let graph = Self::new_in(alloc);
+        let result = build(&graph);
+        let actual = graph.shape();
+        if &actual == shape {
+            Ok((graph, result))
+        } else {
+            Err(ShapeMismatch { expected: shape.clone(), actual })
+        }
+    }
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Get the structure of this graph (without values or closures).
+    pub fn shape(&self) -> GraphShape {
+        let mut remaining_outputs = 0;
+        GraphShape(self.elems().iter().map(|elem| match elem {
+            RxDAGElemRef::Node(_) => if remaining_outputs > 0 {
+                remaining_outputs -= 1;
+                ElemShape::CRx
+            } else {
+                ElemShape::Var
+            },
+            RxDAGElemRef::Edge(edge) => {
+                remaining_outputs = edge.num_outputs();
+                ElemShape::Edge {
+                    input_backwards_offsets: edge.input_backwards_offsets().to_vec(),
+                    num_outputs: edge.num_outputs()
+                }
+            }
+        }).collect())
+    }
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Render the graph as an indented tree, for debugging.
+    ///
+    /// The roots are nodes which no computation reads, and each node's dependencies are indented beneath it.
+    /// Dependencies shared by multiple nodes are rendered multiple times.
+    /// Values are stored type-erased, so each node shows its type instead.
+    pub fn tree_string(&self) -> String {
+        let mut is_read = vec![false; self.elems().len()];
+        for (index, elem) in self.elems().iter().enumerate() {
+            if let RxDAGElemRef::Edge(edge) = elem {
+                for offset in edge.input_backwards_offsets() {
+                    is_read[index - offset] = true;
+                }
+            }
+        }
+
+        let mut result = String::new();
+        for (index, elem) in self.elems().iter().enumerate() {
+            if let RxDAGElemRef::Node(_) = elem {
+                if !is_read[index] {
+                    self.write_tree(&mut result, index, 0);
+                }
+            }
+        }
+        result
+    }
+
+    fn write_tree(&self, result: &mut String, index: usize, depth: usize) {
+        let node = match self.elems().get(index) {
+            Some(RxDAGElemRef::Node(node)) => node,
+            _ => unreachable!("write_tree called on an edge")
+        };
+        let kind = if self.producing_edge_index(index).is_some() { "crx" } else { "var" };
+        result.push_str(&"  ".repeat(depth));
+        result.push_str(&format!("{}#{}: {}\n", kind, index, node.value_type_name()));
+        for dependency in self.dependencies_of(UntypedRxRef::new(self, index)) {
+            self.write_tree(result, dependency.index(), depth + 1);
+        }
+    }
+}
+
+impl Display for ShapeMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph shape mismatch: expected {} elements, got {}", self.expected.0.len(), self.actual.0.len())?;
+        if let Some(index) = self.expected.0.iter().zip(self.actual.0.iter()).position(|(expected, actual)| expected != actual) {
+            write!(f, ", first difference at index {}", index)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ShapeMismatch {}
+
+#[cfg(feature = "serde")]
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Get the structure of this graph (see [RxDAG::shape]) as JSON, e.g. for a web-based visualizer:
+    /// `{ "nodes": [{ "index", "kind" }], "edges": [{ "index", "inputs", "outputs" }] }`,
+    /// where `kind` is `"var"` or `"crx"`, and `inputs` and `outputs` are node indices.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        for (index, elem) in self.shape().0.into_iter().enumerate() {
+            match elem {
+                ElemShape::Var => nodes.push(serde_json::json!({ "index": index, "kind": "var" })),
+                ElemShape::CRx => nodes.push(serde_json::json!({ "index": index, "kind": "crx" })),
+                ElemShape::Edge { input_backwards_offsets, num_outputs } => edges.push(serde_json::json!({
+                    "index": index,
+                    "inputs": input_backwards_offsets.iter().map(|offset| index - offset).collect::<Vec<_>>(),
+                    // Outputs are always right after the edge
+                    "outputs": (index + 1..=index + num_outputs).collect::<Vec<_>>()
+                }))
+            }
+        }
+        serde_json::json!({ "nodes": nodes, "edges": edges })
+    }
+}
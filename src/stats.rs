@@ -0,0 +1,81 @@
+use std::alloc::Allocator;
+use std::collections::HashMap;
+use std::mem::{size_of, size_of_val};
+use crate::dag::RxDAG;
+use crate::rx_impl::{RxDAGElem, RxDAGElemRef};
+
+/// Size of an [RxDAG]. See [RxDAG::stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GraphStats {
+    /// Number of variables and computed values
+    pub node_count: usize,
+    /// Number of computations (including side-effects)
+    pub edge_count: usize,
+    /// Number of nodes and edges
+    pub total_elements: usize,
+    /// Estimate of the memory used by the graph.
+    /// Doesn't include data which values or closures own on the heap.
+    pub approx_bytes: usize
+}
+
+/// A computation in an [RxDAG]. See [RxDAG::edges].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EdgeInfo {
+    /// Index of the edge in the DAG
+    pub index: usize,
+    /// Indices of the nodes the edge read when it last computed, in graph order
+    pub inputs: Vec<usize>,
+    /// Number of computed values the edge outputs, which immediately follow it (0 for side-effects)
+    pub num_outputs: usize,
+    /// The tag of a side-effect from [RxDAG::run_crx_tagged]
+    pub tag: Option<u32>
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Get the size of this graph.
+    ///
+    /// Nodes are never removed until the entire graph is freed (see [RxDAG::compact]), so this is useful to monitor graphs which keep growing.
+    pub fn stats(&self) -> GraphStats {
+        let mut stats = GraphStats::default();
+        for elem in self.elems().iter() {
+            stats.total_elements += 1;
+            stats.approx_bytes += size_of::<RxDAGElem<'c, A>>();
+            match elem {
+                RxDAGElemRef::Node(node) => {
+                    stats.node_count += 1;
+                    stats.approx_bytes += node.value_size();
+                }
+                RxDAGElemRef::Edge(edge) => {
+                    stats.edge_count += 1;
+                    stats.approx_bytes += size_of_val(edge) + size_of_val(edge.input_backwards_offsets());
+                }
+            }
+        }
+        stats
+    }
+
+    /// Get every edge (computation or side-effect) in graph order, e.g. to display them in a profiler.
+    pub fn edges(&self) -> Vec<EdgeInfo> {
+        self.elems().iter().enumerate().filter_map(|(index, elem)| match elem {
+            RxDAGElemRef::Node(_) => None,
+            RxDAGElemRef::Edge(edge) => {
+                let mut inputs = edge.input_backwards_offsets().iter().map(|offset| index - offset).collect::<Vec<_>>();
+                inputs.sort_unstable();
+                Some(EdgeInfo {
+                    index,
+                    inputs,
+                    num_outputs: edge.num_outputs(),
+                    tag: edge.tag()
+                })
+            }
+        }).collect()
+    }
+
+    /// Map each edge's index to the indices of the nodes it read when it last computed, in graph order.
+    ///
+    /// Unlike the dependencies when the edge was created, this includes reads which depend on values,
+    /// e.g. only the branch a computation took the last time it ran.
+    pub fn last_read_map(&self) -> HashMap<usize, Vec<usize>> {
+        self.edges().into_iter().map(|edge| (edge.index, edge.inputs)).collect()
+    }
+}
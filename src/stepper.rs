@@ -0,0 +1,68 @@
+use std::alloc::{Allocator, Global};
+use crate::dag::{RxDAG, RxDAGSnapshot};
+
+/// Recomputes an [RxDAG] one element at a time. See [RxDAG::recompute_stepper].
+///
+/// Between steps you can inspect the partially-recomputed graph with [RecomputeStepper::snapshot]:
+/// nodes before [RecomputeStepper::index] have their new values, and nodes after have their old ones.
+/// If the stepper is dropped before it's done, it finishes the recompute.
+pub struct RecomputeStepper<'a, 'c, A: Allocator = Global> {
+    graph: &'a mut RxDAG<'c, A>,
+    index: usize,
+    is_done: bool
+}
+
+impl<'a, 'c, A: Allocator> RecomputeStepper<'a, 'c, A> {
+    pub(crate) fn new(graph: &'a mut RxDAG<'c, A>) -> Self {
+        RecomputeStepper {
+            graph,
+            index: 0,
+            is_done: false
+        }
+    }
+
+    /// Index of the next element to recompute
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Whether every element has been recomputed
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    /// Recompute the next element. After the last element, finishes the recompute (e.g. runs deferred side-effects).
+    ///
+    /// Returns `false` if the recompute is done.
+    pub fn step(&mut self) -> bool {
+        if self.is_done {
+            return false;
+        }
+        if self.index < self.graph.elems().len() {
+            self.graph.recompute_elem(self.index);
+            self.index += 1;
+        }
+        if self.index == self.graph.elems().len() {
+            self.graph.finish_recompute();
+            self.is_done = true;
+        }
+        true
+    }
+
+    /// Read the partially-recomputed graph.
+    ///
+    /// Values which alias another node ([RxDAG::new_crx_cow], [RxDAG::new_alias_crx]) always read that node,
+    /// so they may already have its new value even if they're after [RecomputeStepper::index].
+    pub fn snapshot(&self) -> RxDAGSnapshot<'_, 'c, A> {
+        self.graph.stale()
+    }
+}
+
+impl<'a, 'c, A: Allocator> Drop for RecomputeStepper<'a, 'c, A> {
+    fn drop(&mut self) {
+        // Don't recompute more if a computation panicked
+        if !std::thread::panicking() {
+            while self.step() {}
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::alloc::{Allocator, Global};
+use std::fmt::{Debug, Formatter};
+use crate::dag::{RxDAG, RxContext};
+use crate::rx_ref::Var;
+
+/// Saved values of some [Var]s, which can later be compared against ([RxDAG::values_eq])
+/// or restored ([RxDAG::restore]).
+///
+/// Useful for "golden-state" tests: save, perform operations, undo them, and assert the graph is back.
+pub struct VarSnapshot<'c, A: Allocator = Global>(Vec<Box<dyn SavedVar<'c, A> + 'c>>);
+
+trait SavedVar<'c, A: Allocator> {
+    fn eq_current(&self, graph: &RxDAG<'c, A>) -> bool;
+    fn restore(&self, graph: &RxDAG<'c, A>);
+}
+
+struct SavedVarImpl<'c, T, A: Allocator> {
+    var: Var<'c, T, A>,
+    value: T
+}
+
+impl<'c, A: Allocator + 'c> VarSnapshot<'c, A> {
+    /// Create an empty snapshot
+    pub fn new() -> Self {
+        VarSnapshot(Vec::new())
+    }
+
+    /// Save the current value of `var`
+    pub fn save<'a, T: Clone + PartialEq + 'c>(&mut self, c: impl RxContext<'a, 'c, A>, var: Var<'c, T, A>) where 'c: 'a {
+        let value = var.get(c).clone();
+        self.0.push(Box::new(SavedVarImpl { var, value }));
+    }
+
+    /// Save the current value of `var` and return `self`
+    pub fn with<'a, T: Clone + PartialEq + 'c>(mut self, c: impl RxContext<'a, 'c, A>, var: Var<'c, T, A>) -> Self where 'c: 'a {
+        self.save(c, var);
+        self
+    }
+
+    /// Number of saved variables
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no variables are saved
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'c, A: Allocator + 'c> Default for VarSnapshot<'c, A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'c, A: Allocator> Debug for VarSnapshot<'c, A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VarSnapshot")
+            .field("len", &self.0.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'c, T: Clone + PartialEq + 'c, A: Allocator + 'c> SavedVar<'c, A> for SavedVarImpl<'c, T, A> {
+    fn eq_current(&self, graph: &RxDAG<'c, A>) -> bool {
+        self.var.get(graph.stale()) == &self.value
+    }
+
+    fn restore(&self, graph: &RxDAG<'c, A>) {
+        self.var.set(graph, self.value.clone());
+    }
+}
+
+impl<'c, A: Allocator + 'c> RxDAG<'c, A> {
+    /// Whether the committed values of all variables in `snapshot` equal their saved values.
+    ///
+    /// Like [RxDAG::stale] this doesn't recompute, so newly-set values aren't compared.
+    pub fn values_eq(&self, snapshot: &VarSnapshot<'c, A>) -> bool {
+        snapshot.0.iter().all(|saved| saved.eq_current(self))
+    }
+
+    /// Set all variables in `snapshot` back to their saved values.
+    /// The changes will be applied on recompute.
+    pub fn restore(&self, snapshot: &VarSnapshot<'c, A>) {
+        for saved in &snapshot.0 {
+            saved.restore(self);
+        }
+    }
+}
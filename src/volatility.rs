@@ -0,0 +1,56 @@
+use std::alloc::Allocator;
+use crate::dag::RxDAG;
+use crate::rx_impl::RxDAGElemRef;
+use crate::rx_ref::UntypedRxRef;
+
+/// Records when each node last changed. See [RxDAG::track_volatility].
+#[derive(Debug)]
+pub(crate) struct VolatilityTracker {
+    window: u64,
+    // Revision each node last changed, or when it was first tracked if it hasn't changed since
+    last_changes: Vec<u64>
+}
+
+impl<'c, A: Allocator> RxDAG<'c, A> {
+    /// Start recording when nodes change, so [RxDAG::constant_candidates] can find computed values
+    /// which didn't change in the last `window` recomputes. Calling this again resets the recording.
+    pub fn track_volatility(&mut self, window: usize) {
+        let last_changes = vec![self.revision(); self.elems().len()];
+        *self.volatility_mut() = Some(VolatilityTracker {
+            window: window as u64,
+            last_changes
+        });
+    }
+
+    /// Computed values which didn't change in the last `window` recomputes (see [RxDAG::track_volatility]),
+    /// so they may be replaceable with constants. Returns nothing if volatility isn't being tracked.
+    ///
+    /// Nodes are only reported after they have been tracked for `window` recomputes.
+    pub fn constant_candidates(&self) -> Vec<UntypedRxRef<'c, A>> {
+        let tracker = match self.volatility() {
+            None => return Vec::new(),
+            Some(tracker) => tracker
+        };
+        tracker.last_changes.iter().enumerate().filter(|(index, last_change)| {
+            self.revision() - **last_change >= tracker.window &&
+                matches!(self.elems().get(*index), Some(RxDAGElemRef::Node(_))) &&
+                self.producing_edge_index(*index).is_some()
+        }).map(|(index, _)| UntypedRxRef::new(self, index)).collect()
+    }
+
+    /// Record the nodes which changed in the current recompute
+    pub(crate) fn record_volatility(&mut self) {
+        if self.volatility().is_none() {
+            return;
+        }
+        let revision = self.revision();
+        let num_elems = self.elems().len();
+        let recomputed = self.recomputed().to_vec();
+        let tracker = self.volatility_mut().as_mut().unwrap();
+        // Nodes created since the last recompute are tracked from the last recompute
+        tracker.last_changes.resize(num_elems, revision - 1);
+        for index in recomputed {
+            tracker.last_changes[index] = revision;
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use mini_rx::*;
 use test_log::test;
 use std::cell::{Cell, RefCell};
+use std::borrow::Cow;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 
 #[test]
 fn test_rx() {
@@ -165,6 +167,48 @@ fn test_crx() {
     }
 }
 
+#[test]
+fn test_recompute_stepper() {
+    let mut g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    let crx3 = g.new_crx(move |g| crx2.get(g).to_string());
+    rx.set(&g, vec![2, 3, 4]);
+    {
+        let mut stepper = g.recompute_stepper();
+        // var
+        assert!(stepper.step());
+        assert_eq!(rx.get(stepper.snapshot()), &vec![2, 3, 4]);
+        assert_eq!(*crx.get(stepper.snapshot()), 2);
+        // edge to crx, crx
+        assert!(stepper.step());
+        assert!(stepper.step());
+        assert_eq!(*crx.get(stepper.snapshot()), 4);
+        assert_eq!(*crx2.get(stepper.snapshot()), 22);
+        // edge to crx2, crx2
+        assert!(stepper.step());
+        assert!(stepper.step());
+        assert_eq!(*crx2.get(stepper.snapshot()), 34);
+        assert_eq!(&*crx3.get(stepper.snapshot()), "22");
+        // edge to crx3, crx3
+        assert!(stepper.step());
+        assert!(!stepper.is_done());
+        assert!(stepper.step());
+        assert_eq!(&*crx3.get(stepper.snapshot()), "34");
+        assert!(stepper.is_done());
+        assert!(!stepper.step());
+    }
+    assert_eq!(&*crx3.get(g.now()), "34");
+    // Dropping the stepper early finishes the recompute
+    rx.set(&g, vec![3, 4, 5]);
+    {
+        let mut stepper = g.recompute_stepper();
+        stepper.step();
+    }
+    assert_eq!(&*crx3.get(g.stale()), "46");
+}
+
 #[test]
 fn test_readme() {
     // setup
@@ -271,4 +315,2048 @@ fn stream_like() {
         g.recompute();
     }
     assert_eq!(&*stream.borrow(), &vec![0.0, 1.5, 2.4, 3.8]);
-}
\ No newline at end of file
+}
+#[test]
+fn test_gated_crx() {
+    let num_computes = Cell::new(0);
+    let num_computes_ref = &num_computes;
+
+    let mut g = RxDAG::new();
+    let gate = g.new_var(true);
+    let var = g.new_var(1);
+    let crx = g.new_gated_crx(gate, move |g| {
+        num_computes_ref.set(num_computes_ref.get() + 1);
+        *var.get(g) * 2
+    });
+    assert_eq!(crx.get(g.now()), &2);
+    assert_eq!(num_computes.get(), 1);
+
+    var.set(&g, 2);
+    assert_eq!(crx.get(g.now()), &4);
+    assert_eq!(num_computes.get(), 2);
+
+    // Closing the gate retains the value and misses input changes
+    gate.set(&g, false);
+    assert_eq!(crx.get(g.now()), &4);
+    var.set(&g, 3);
+    assert_eq!(crx.get(g.now()), &4);
+    var.set(&g, 4);
+    assert_eq!(crx.get(g.now()), &4);
+    assert_eq!(num_computes.get(), 2);
+
+    // Reopening the gate recomputes with the current inputs
+    gate.set(&g, true);
+    assert_eq!(crx.get(g.now()), &8);
+    assert_eq!(num_computes.get(), 3);
+    var.set(&g, 5);
+    assert_eq!(crx.get(g.now()), &10);
+    assert_eq!(num_computes.get(), 4);
+}
+
+#[test]
+fn test_as_ptr() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let var_ptr = var.as_ptr(g.now());
+    let crx_ptr = crx.as_ptr(g.now());
+    // Adding nodes doesn't move existing values
+    for i in 0..64 {
+        g.new_var(i);
+    }
+    assert_eq!(unsafe { *var_ptr }, 1);
+    assert_eq!(unsafe { *crx_ptr }, 2);
+    assert_eq!(var_ptr, var.get(g.now()) as *const i32);
+    assert_eq!(crx_ptr, crx.get(g.now()) as *const i32);
+}
+
+#[test]
+fn test_crx_when() {
+    let num_computes = Cell::new(0);
+    let num_computes_ref = &num_computes;
+
+    let mut g = RxDAG::new();
+    let a = g.new_var(1);
+    let b = g.new_var(10);
+    let a_raw = a.raw().raw();
+    let crx = g.new_crx_when(move |changed| {
+        assert_eq!(changed.len(), 2);
+        changed.contains(a_raw)
+    }, move |g| {
+        num_computes_ref.set(num_computes_ref.get() + 1);
+        *a.get(g) + *b.get(g)
+    });
+    assert_eq!(crx.get(g.now()), &11);
+    assert_eq!(num_computes.get(), 1);
+
+    // Only b changed, so the predicate skips the recompute
+    b.set(&g, 20);
+    assert_eq!(crx.get(g.now()), &11);
+    assert_eq!(num_computes.get(), 1);
+
+    // a changed, so it recomputes with the latest b
+    a.set(&g, 2);
+    assert_eq!(crx.get(g.now()), &22);
+    assert_eq!(num_computes.get(), 2);
+
+    a.set(&g, 3);
+    b.set(&g, 30);
+    assert_eq!(crx.get(g.now()), &33);
+    assert_eq!(num_computes.get(), 3);
+}
+
+#[test]
+fn test_var_snapshot() {
+    let mut g = RxDAG::new();
+    let var1 = g.new_var(1);
+    let var2 = g.new_var(String::from("hello"));
+    let crx = g.new_crx(move |g| format!("{}-{}", var2.get(g), var1.get(g)));
+
+    let snapshot = VarSnapshot::new()
+        .with(g.now(), var1)
+        .with(g.now(), var2);
+    assert_eq!(snapshot.len(), 2);
+    assert!(g.values_eq(&snapshot));
+
+    var1.set(&g, 2);
+    var2.set(&g, String::from("world"));
+    // Not recomputed yet
+    assert!(g.values_eq(&snapshot));
+    g.recompute();
+    assert!(!g.values_eq(&snapshot));
+    assert_eq!(crx.get(g.now()), "world-2");
+
+    // Undo
+    g.restore(&snapshot);
+    g.recompute();
+    assert!(g.values_eq(&snapshot));
+    assert_eq!(crx.get(g.now()), "hello-1");
+}
+
+#[test]
+fn test_run_crx_deferred() {
+    let log = RefCell::new(Vec::new());
+    let log_ref = &log;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    g.run_crx_deferred(move |g| {
+        log_ref.borrow_mut().push(format!("deferred {}", var.get(g)));
+    });
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    g.run_crx(move |g| {
+        log_ref.borrow_mut().push(format!("effect {}", crx.get(g)));
+    });
+    let crx2 = g.new_crx(move |g| *crx.get(g) + 1);
+    g.run_crx_deferred(move |g| {
+        log_ref.borrow_mut().push(format!("deferred crx2 {}", crx2.get(g)));
+    });
+    assert_eq!(&*log.borrow(), &vec!["deferred 1", "effect 2", "deferred crx2 3"]);
+    log.borrow_mut().clear();
+
+    // Deferred side-effects run after everything else, even though one was created first
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(&*log.borrow(), &vec!["effect 4", "deferred 2", "deferred crx2 5"]);
+    log.borrow_mut().clear();
+
+    // And they don't run when their inputs don't change
+    g.recompute();
+    assert!(log.borrow().is_empty());
+}
+
+#[test]
+fn test_get_or() {
+    let later = Cell::new(None);
+    let later_ref = &later;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| {
+        let value = *var.get(g);
+        match later_ref.get() {
+            None => value,
+            // later is created after this so it's out of range
+            Some(later) => *CRx::get_or(later, g, &-1)
+        }
+    });
+    let later_crx = g.new_crx(move |g| *var.get(g) * 10);
+    later.set(Some(later_crx));
+    assert_eq!(crx.get(g.now()), &1);
+    assert_eq!(later_crx.get_or(g.now(), &-1), &10);
+
+    var.set(&g, 2);
+    assert_eq!(crx.get(g.now()), &-1);
+    assert_eq!(later_crx.try_get(g.now()), Some(&20));
+
+    // Refs from a different graph aren't read
+    let mut g2 = RxDAG::new();
+    let _ = g2.new_var(0);
+    let other_crx = g2.new_crx(move |_| 5);
+    assert_eq!(other_crx.get_or(g.now(), &-1), &-1);
+    assert_eq!(other_crx.try_get(g2.now()), Some(&5));
+}
+
+#[test]
+fn test_split_indices() {
+    let mut g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    {
+        let split = rx.split_indices(3);
+        assert_eq!(split.len(), 3);
+        assert_eq!(split[0].get(g.now()), &1);
+        assert_eq!(split[1].get(g.now()), &2);
+        assert_eq!(split[2].get(g.now()), &3);
+        split[1].set(&g, 5);
+        assert_eq!(rx.get(g.now()), &vec![1, 5, 3]);
+        assert_eq!(split[1].get(g.now()), &5);
+        split[0].set(&g, 2);
+        split[1].set(&g, 3);
+        split[2].set(&g, 4);
+    }
+    assert_eq!(rx.get(g.now()), &vec![2, 3, 4]);
+}
+
+#[test]
+fn test_get_mut() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(vec![1, 2, 3]);
+    let crx = g.new_crx(move |g| var.get(g).iter().sum::<i32>());
+    assert_eq!(crx.get(g.now()), &6);
+
+    g.get_mut(var).push(4);
+    assert_eq!(var.get(g.stale()), &vec![1, 2, 3, 4]);
+    assert_eq!(crx.get(g.stale()), &6);
+    assert_eq!(crx.get(g.now()), &10);
+
+    // Mutates the latest value if there is a pending set
+    var.set(&g, vec![5]);
+    g.get_mut(var).push(6);
+    assert_eq!(var.get(g.now()), &vec![5, 6]);
+    assert_eq!(crx.get(g.now()), &11);
+}
+
+#[test]
+fn test_iter_items() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(3);
+    let crx = g.new_crx(move |g| (0..*var.get(g)).map(|i| i * 10).collect::<Vec<_>>());
+    assert_eq!(crx.iter_items(g.now()).copied().collect::<Vec<_>>(), vec![0, 10, 20]);
+    var.set(&g, 4);
+    assert_eq!(crx.iter_items(g.now()).copied().collect::<Vec<_>>(), vec![0, 10, 20, 30]);
+}
+
+#[test]
+fn test_with_var_override() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    assert_eq!(crx.get(g.now()), &2);
+
+    let result = g.with_var_override(var, 5, |g| {
+        assert_eq!(var.get(g.now()), &5);
+        *crx.get(g.now())
+    });
+    assert_eq!(result, 10);
+    assert_eq!(var.get(g.now()), &1);
+    assert_eq!(crx.get(g.now()), &2);
+}
+
+#[test]
+fn test_recompute_catching() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let checked = g.new_crx(move |g| {
+        let value = *crx.get(g);
+        if value > 10 {
+            panic!("value too large");
+        }
+        value
+    });
+    let var2 = g.new_var(0);
+    assert!(g.recompute_catching().is_ok());
+    assert_eq!(checked.get(g.stale()), &2);
+
+    var.set(&g, 6);
+    var2.set(&g, 5);
+    let panic = g.recompute_catching().unwrap_err();
+    // var, edge, crx, edge (panics), checked, var2
+    assert_eq!(panic.index, 3);
+    assert_eq!(panic.message(), Some("value too large"));
+    // Nodes before the edge are committed, nodes after it aren't (even `checked`, which is computed from `crx`),
+    // and pending values after it are kept for the next recompute
+    assert_eq!(var.get(g.stale()), &6);
+    assert_eq!(crx.get(g.stale()), &12);
+    assert_eq!(checked.get(g.stale()), &2);
+    assert_eq!(var2.get(g.stale()), &0);
+    assert!(g.is_dirty());
+
+    // The edge reruns with the latest value of its inputs
+    var.set(&g, 3);
+    assert!(g.recompute_catching().is_ok());
+    assert_eq!(checked.get(g.stale()), &6);
+    assert_eq!(var2.get(g.stale()), &5);
+    var.set(&g, 4);
+    assert!(g.recompute_catching().is_ok());
+    assert_eq!(checked.get(g.stale()), &8);
+}
+
+#[test]
+fn test_recompute_catching_keeps_later_changes() {
+    let should_panic = Cell::new(false);
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let should_panic = &should_panic;
+    let checked = g.new_crx(move |g| {
+        if should_panic.get() {
+            panic!("checked failed");
+        }
+        *var.get(g)
+    });
+    let doubled = g.new_crx(move |g| *var.get(g) * 2);
+    let var2 = g.new_var(0);
+    let sum = g.new_crx(move |g| *var2.get(g) + *doubled.get(g));
+    assert_eq!(sum.get(g.now()), &2);
+
+    should_panic.set(true);
+    var.set(&g, 20);
+    var2.set(&g, 5);
+    // var, edge (panics), checked, edge, doubled, var2, edge, sum
+    assert_eq!(g.recompute_catching().unwrap_err().index, 1);
+    assert_eq!(doubled.get(g.stale()), &2);
+    assert_eq!(var2.get(g.stale()), &0);
+
+    // The set after the panicking edge survives, and edges after it see the change before it
+    should_panic.set(false);
+    assert!(g.recompute_catching().is_ok());
+    assert_eq!(checked.get(g.stale()), &20);
+    assert_eq!(var2.get(g.stale()), &5);
+    assert_eq!(doubled.get(g.stale()), &40);
+    assert_eq!(sum.get(g.stale()), &45);
+}
+
+#[test]
+fn test_recompute_catching_discard_pending() {
+    let should_panic = Cell::new(false);
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let should_panic = &should_panic;
+    let checked = g.new_crx(move |g| {
+        if should_panic.get() {
+            panic!("checked failed");
+        }
+        *var.get(g)
+    });
+    let var2 = g.new_var(0);
+    let sum = g.new_crx(move |g| *var2.get(g) + *var.get(g));
+    assert_eq!(sum.get(g.now()), &1);
+
+    should_panic.set(true);
+    var.set(&g, 20);
+    var2.set(&g, 5);
+    // var, edge (panics), checked, var2, edge, sum
+    assert_eq!(g.recompute_catching().unwrap_err().index, 1);
+    assert!(g.would_recompute());
+
+    // The set after the panicking edge is rolled back, the one before it was already committed
+    g.discard_pending();
+    assert!(!g.would_recompute());
+    should_panic.set(false);
+    assert!(g.recompute_catching().is_ok());
+    assert_eq!(checked.get(g.stale()), &20);
+    assert_eq!(var2.get(g.stale()), &0);
+    assert_eq!(sum.get(g.stale()), &20);
+}
+
+#[test]
+fn test_as_of() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let var2 = g.new_var(3);
+
+    // var, edge, crx, var2
+    let input = g.now().as_of(3);
+    assert_eq!(var.raw().try_get(input), Some(&1));
+    assert_eq!(crx.try_get(input), Some(&2));
+    assert_eq!(var2.raw().try_get(input), None);
+
+    let input = g.now().as_of(2);
+    assert_eq!(var.raw().try_get(input), Some(&1));
+    assert_eq!(crx.try_get(input), None);
+
+    let input = g.now().as_of(100);
+    assert_eq!(var2.get(input), &3);
+}
+
+#[test]
+fn test_crx_pipeline() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(10);
+    let stages = g.new_crx_pipeline()
+        .stage(move |g, _| *var.get(g) * 2)
+        .stage(move |g, prev| *prev[0].get(g) + *var2.get(g))
+        .stage(move |g, prev| *prev[0].get(g) * *prev[1].get(g))
+        .build();
+    assert_eq!(stages.len(), 3);
+    assert_eq!(stages[0].get(g.now()), &2);
+    assert_eq!(stages[1].get(g.now()), &12);
+    assert_eq!(stages[2].get(g.now()), &24);
+
+    var.set(&g, 2);
+    assert_eq!(stages[0].get(g.now()), &4);
+    assert_eq!(stages[1].get(g.now()), &14);
+    assert_eq!(stages[2].get(g.now()), &56);
+
+    var2.set(&g, 20);
+    assert_eq!(stages[0].get(g.now()), &4);
+    assert_eq!(stages[1].get(g.now()), &24);
+    assert_eq!(stages[2].get(g.now()), &96);
+}
+
+#[test]
+fn test_same_node() {
+    let g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g));
+    assert!(var.raw().raw().same_node(var.raw().raw()));
+    assert!(var.same_node(var));
+    assert!(!var.same_node(var2));
+    assert!(!var.raw().raw().same_node(crx.raw().raw()));
+    assert!(crx.same_node(crx));
+
+    // Same index in a different graph
+    let g2 = RxDAG::new();
+    let other_var = g2.new_var(1);
+    assert!(!var.same_node(other_var));
+}
+
+#[test]
+fn test_crx_with() {
+    let eager_computes = Cell::new(0);
+    let lazy_computes = Cell::new(0);
+    let on_demand_computes = Cell::new(0);
+    let (eager_computes_ref, lazy_computes_ref, on_demand_computes_ref) = (&eager_computes, &lazy_computes, &on_demand_computes);
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let eager = g.new_crx_with(Laziness::Eager, move |g| {
+        eager_computes_ref.set(eager_computes_ref.get() + 1);
+        *var.get(g) * 2
+    });
+    let lazy = g.new_crx_with(Laziness::Lazy, move |g| {
+        lazy_computes_ref.set(lazy_computes_ref.get() + 1);
+        *var.get(g) * 3
+    });
+    let on_demand = g.new_crx_with(Laziness::OnDemand, move |g| {
+        on_demand_computes_ref.set(on_demand_computes_ref.get() + 1);
+        *var.get(g) * 4
+    });
+    assert_eq!(eager_computes.get(), 1);
+    assert_eq!(lazy_computes.get(), 0);
+    assert_eq!(on_demand_computes.get(), 0);
+
+    // Not computed until read
+    var.set(&g, 2);
+    assert_eq!(lazy.get_or(g.now(), &-1), &-1);
+    assert_eq!(lazy_computes.get(), 0);
+    assert_eq!(eager_computes.get(), 2);
+    assert_eq!(lazy.get(g.now()), &6);
+    assert_eq!(on_demand.get(g.now()), &8);
+    assert_eq!(lazy_computes.get(), 1);
+    assert_eq!(on_demand_computes.get(), 1);
+
+    // After being read, lazy is eager, but on-demand waits for the next read
+    var.set(&g, 3);
+    assert_eq!(eager.get(g.now()), &6);
+    assert_eq!(lazy_computes.get(), 2);
+    assert_eq!(on_demand_computes.get(), 1);
+    assert_eq!(on_demand.try_get(g.now()), None);
+    assert_eq!(lazy.get(g.now()), &9);
+    assert_eq!(on_demand.get(g.now()), &12);
+    assert_eq!(on_demand.get(g.now()), &12);
+    assert_eq!(on_demand_computes.get(), 2);
+
+    // Lazy values read from computes are still tracked
+    let sum = g.new_crx(move |g| *lazy.get(g) + *on_demand.get(g));
+    assert_eq!(sum.get(g.now()), &21);
+    var.set(&g, 4);
+    assert_eq!(sum.get(g.now()), &28);
+    assert_eq!(on_demand_computes.get(), 3);
+
+    // Computing a lazy value inside another compute doesn't lose the outer compute's reads
+    let var2 = g.new_var(1);
+    let lazy2 = g.new_crx_with(Laziness::Lazy, move |g| *var.get(g) + 1);
+    let outer = g.new_crx(move |g| *var2.get(g) * 100 + *lazy2.get(g));
+    assert_eq!(outer.get(g.now()), &105);
+    var2.set(&g, 2);
+    assert_eq!(outer.get(g.now()), &205);
+    var.set(&g, 5);
+    assert_eq!(outer.get(g.now()), &206);
+}
+
+#[test]
+fn test_reset_tracking() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+
+    let mut g = RxDAG::new();
+    let var1 = g.new_var(1);
+    let var2 = g.new_var(2);
+    let crx = g.new_crx(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        let value1 = *var1.get(g);
+        // var1 is no longer a dependency
+        g.reset_tracking();
+        value1 + *var2.get(g)
+    });
+    assert_eq!(crx.get(g.now()), &3);
+    assert_eq!(computes.get(), 1);
+
+    var1.set(&g, 10);
+    assert_eq!(crx.get(g.now()), &3);
+    assert_eq!(computes.get(), 1);
+
+    var2.set(&g, 20);
+    assert_eq!(crx.get(g.now()), &30);
+    assert_eq!(computes.get(), 2);
+}
+
+#[test]
+fn test_for_each_subscriber() {
+    let received = RefCell::new(Vec::new());
+    let received_ref = &received;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let other_var = g.new_var(0);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    crx.for_each_subscriber(&g, vec![
+        Box::new(move |value| received_ref.borrow_mut().push(("a", *value))),
+        Box::new(move |value| received_ref.borrow_mut().push(("b", *value)))
+    ]);
+    assert_eq!(*received.borrow(), vec![("a", 2), ("b", 2)]);
+
+    other_var.set(&g, 1);
+    g.recompute();
+    assert_eq!(*received.borrow(), vec![("a", 2), ("b", 2)]);
+
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(*received.borrow(), vec![("a", 2), ("b", 2), ("a", 4), ("b", 4)]);
+}
+
+#[test]
+fn test_warn_on_stale_read() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    g.warn_on_stale_read(true);
+    assert!(!g.is_dirty());
+
+    // Not dirty, so not stale
+    assert_eq!(crx.get(g.stale()), &2);
+    assert_eq!(g.stale_reads(), 0);
+
+    var.set(&g, 2);
+    assert!(g.is_dirty());
+    assert_eq!(var.get(g.stale()), &1);
+    assert_eq!(crx.get(g.stale()), &2);
+    assert_eq!(g.stale_reads(), 2);
+
+    assert_eq!(crx.get(g.now()), &4);
+    assert!(!g.is_dirty());
+    assert_eq!(g.stale_reads(), 2);
+
+    // Only when enabled
+    g.warn_on_stale_read(false);
+    var.set(&g, 3);
+    assert_eq!(var.get(g.stale()), &2);
+    assert_eq!(g.stale_reads(), 2);
+}
+
+#[test]
+fn test_crx_cow() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(vec![1, 2, 3]);
+    let pair = g.new_var((1, 2));
+    let filter = g.new_var(true);
+    let filtered = g.new_crx_cow(move |g| if *filter.get(g) {
+        Cow::Borrowed(var.get(g))
+    } else {
+        Cow::Owned(Vec::new())
+    });
+    let first = g.new_crx_cow(move |g| Cow::Borrowed(&pair.get(g).0));
+
+    // Borrowing an entire input aliases it
+    assert_eq!(filtered.get(g.now()), &vec![1, 2, 3]);
+    assert_eq!(filtered.as_ptr(g.now()), var.as_ptr(g.now()));
+    var.set(&g, vec![4, 5]);
+    assert_eq!(filtered.get(g.now()), &vec![4, 5]);
+    assert_eq!(filtered.as_ptr(g.now()), var.as_ptr(g.now()));
+
+    filter.set(&g, false);
+    assert_eq!(filtered.get(g.now()), &Vec::<i32>::new());
+    assert_ne!(filtered.as_ptr(g.now()), var.as_ptr(g.now()));
+
+    // Borrowing part of an input clones it
+    assert_eq!(first.get(g.now()), &1);
+    assert_ne!(first.as_ptr(g.now()) as *const (), pair.as_ptr(g.now()) as *const ());
+    pair.set(&g, (3, 4));
+    assert_eq!(first.get(g.now()), &3);
+}
+
+#[test]
+fn test_crx_cow_alias_of_cow() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(vec![4, 5]);
+    let flag = g.new_var(true);
+    let a = g.new_crx_cow(move |g| if *flag.get(g) {
+        Cow::Owned(vec![1, 2, 3])
+    } else {
+        Cow::Borrowed(var.get(g))
+    });
+    let b = g.new_crx_cow(move |g| Cow::Borrowed(a.get(g)));
+    assert_eq!(b.get(g.now()), &vec![1, 2, 3]);
+
+    // `a` switches from owning to aliasing before `b` recomputes, so `b` must read through `a`
+    flag.set(&g, false);
+    {
+        let mut stepper = g.recompute_stepper();
+        for _ in 0..4 {
+            stepper.step();
+        }
+        assert_eq!(b.get(stepper.snapshot()), &vec![4, 5]);
+    }
+    assert_eq!(b.get(g.now()), &vec![4, 5]);
+    var.set(&g, vec![6]);
+    assert_eq!(b.get(g.now()), &vec![6]);
+    flag.set(&g, true);
+    assert_eq!(b.get(g.now()), &vec![1, 2, 3]);
+}
+
+#[test]
+fn test_on_transition() {
+    let transitions = RefCell::new(Vec::new());
+    let transitions_ref = &transitions;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 10);
+    crx.on_transition(&g, move |old, new| transitions_ref.borrow_mut().push((*old, *new)));
+    assert!(transitions.borrow().is_empty());
+
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(*transitions.borrow(), vec![(10, 20)]);
+
+    g.recompute();
+    var.set(&g, 3);
+    g.recompute();
+    assert_eq!(*transitions.borrow(), vec![(10, 20), (20, 30)]);
+}
+
+#[test]
+fn test_crx_array() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(10);
+    let [crx1, crx2, crx3] = g.new_crx_array(move |g| {
+        let value = *var.get(g);
+        [value, value + *var2.get(g), value * 100]
+    });
+    let crx4 = g.new_crx(move |g| *crx2.get(g) * 2);
+    assert_eq!(crx1.get(g.now()), &1);
+    assert_eq!(crx2.get(g.now()), &11);
+    assert_eq!(crx3.get(g.now()), &100);
+    assert_eq!(crx4.get(g.now()), &22);
+
+    var.set(&g, 2);
+    assert_eq!(crx1.get(g.now()), &2);
+    assert_eq!(crx2.get(g.now()), &12);
+    assert_eq!(crx3.get(g.now()), &200);
+    assert_eq!(crx4.get(g.now()), &24);
+
+    var2.set(&g, 20);
+    assert_eq!(crx2.get(g.now()), &22);
+    assert_eq!(crx4.get(g.now()), &44);
+}
+
+#[test]
+fn test_mismatch_policy() {
+    let mut g = RxDAG::new();
+    let _ = g.new_var(0);
+    let mut g2 = RxDAG::new();
+    let other_var = g2.new_var(1);
+    let other_crx = g2.new_crx(move |g| *other_var.get(g) + 1);
+
+    g.set_mismatch_policy(MismatchPolicy::Log);
+    assert_eq!(other_crx.try_get(g.now()), None);
+    // Sets and introspection are skipped
+    other_var.set(&g, 5);
+    other_var.modify(&g, |x| x + 1);
+    assert!(!g.would_recompute());
+    assert_eq!(g.dependencies_of(other_crx.raw().raw()).len(), 0);
+    assert_eq!(g.transitive_inputs(other_crx.raw().raw()).len(), 0);
+    assert!(!g.did_recompute(other_var.raw().raw()));
+    // There's no value to return
+    assert!(catch_unwind(AssertUnwindSafe(|| *other_crx.get(g.stale()))).is_err());
+
+    g.set_mismatch_policy(MismatchPolicy::Panic);
+    assert!(catch_unwind(AssertUnwindSafe(|| *other_var.get(g.stale()))).is_err());
+    // Also checked in computations
+    let result = catch_unwind(AssertUnwindSafe(|| g.new_crx(move |g| *other_var.get(g))));
+    assert!(result.is_err());
+
+    // The right graph is still fine
+    g2.set_mismatch_policy(MismatchPolicy::Panic);
+    assert_eq!(other_crx.get(g2.now()), &2);
+}
+
+#[test]
+fn test_read_consistent() {
+    let mut g = RxDAG::new();
+    let var1 = g.new_var(1);
+    let var2 = g.new_var(-1);
+    let sum = g.new_crx(move |g| *var1.get(g) + *var2.get(g));
+    g.recompute();
+    let revision = g.revision();
+
+    // Sets aren't visible until recompute, so the values stay consistent
+    let (value1, value2, value_sum) = g.read_consistent(|view| {
+        assert_eq!(view.revision(), revision);
+        let value1 = *var1.get(view);
+        var1.set(&g, 2);
+        var2.set(&g, -2);
+        (value1, *var2.get(view), *sum.get(view))
+    });
+    assert_eq!((value1, value2, value_sum), (1, -1, 0));
+
+    g.recompute();
+    assert_eq!(g.revision(), revision + 1);
+    g.read_consistent(|view| {
+        assert_eq!((*var1.get(view), *var2.get(view), *sum.get(view)), (2, -2, 0));
+    });
+}
+
+#[test]
+fn test_get_untracked() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let aux = g.new_var((10, 20));
+    let aux_first = aux.derive_using_clone(|(first, _)| first, |(old_first, _), first| *old_first = first);
+    let crx = g.new_crx(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        *var.get(g) + *g.get_untracked(&aux_first)
+    });
+    assert_eq!(crx.get(g.now()), &11);
+
+    aux.set(&g, (30, 40));
+    assert_eq!(crx.get(g.now()), &11);
+    assert_eq!(computes.get(), 1);
+
+    var.set(&g, 2);
+    assert_eq!(crx.get(g.now()), &32);
+    assert_eq!(computes.get(), 2);
+}
+
+#[test]
+fn test_watch() {
+    let fired = RefCell::new(Vec::new());
+    let fired_ref = &fired;
+
+    let mut g = RxDAG::new();
+    let var1 = g.new_var(1);
+    let var2 = g.new_var(2);
+    let unwatched = g.new_var(3);
+    let crx1 = g.new_crx(move |g| *var1.get(g) * 10);
+    let crx2 = g.new_crx(move |g| *var2.get(g) * 10);
+    g.watch(&[crx1, crx2], move |values| fired_ref.borrow_mut().push(values.iter().map(|value| **value).collect::<Vec<_>>()));
+    assert_eq!(*fired.borrow(), vec![vec![10, 20]]);
+
+    var1.set(&g, 4);
+    var2.set(&g, 5);
+    g.recompute();
+    assert_eq!(*fired.borrow(), vec![vec![10, 20], vec![40, 50]]);
+
+    unwatched.set(&g, 6);
+    g.recompute();
+    assert_eq!(fired.borrow().len(), 2);
+
+    var2.set(&g, 7);
+    g.recompute();
+    assert_eq!(*fired.borrow(), vec![vec![10, 20], vec![40, 50], vec![40, 70]]);
+}
+
+#[test]
+fn test_index_into() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+
+    let mut g = RxDAG::new();
+    let vars = (0..3).map(|i| g.new_var(i * 10)).collect::<Vec<_>>();
+    let sources = vars.iter().map(|var| {
+        let var = *var;
+        g.new_crx(move |g| *var.get(g) + 1)
+    }).collect::<Vec<_>>();
+    let index = g.new_var(0);
+    let selected = g.index_into(sources.clone(), index);
+    let try_selected = g.try_index_into(sources, index);
+    g.run_crx(move |g| {
+        selected.get(g);
+        computes_ref.set(computes_ref.get() + 1);
+    });
+    assert_eq!(selected.get(g.now()), &1);
+
+    // Only the selected element is a dependency
+    vars[1].set(&g, 100);
+    assert_eq!(selected.get(g.now()), &1);
+    assert_eq!(computes.get(), 1);
+
+    index.set(&g, 1);
+    assert_eq!(selected.get(g.now()), &101);
+    vars[1].set(&g, 200);
+    assert_eq!(selected.get(g.now()), &201);
+    index.set(&g, 2);
+    assert_eq!(selected.get(g.now()), &21);
+    assert_eq!(try_selected.get(g.now()), &Some(21));
+
+    // Out of bounds
+    index.set(&g, 3);
+    assert!(g.recompute_catching().is_err());
+
+    let mut g = RxDAG::new();
+    let sources = vec![g.new_crx(|_| 1)];
+    let index = g.new_var(0);
+    let try_selected = g.try_index_into(sources, index);
+    assert_eq!(try_selected.get(g.now()), &Some(1));
+    index.set(&g, 1);
+    assert_eq!(try_selected.get(g.now()), &None);
+}
+
+#[test]
+fn test_constant() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let constant = g.constant(10);
+    let crx = g.new_crx(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        *constant.get(g) * 2
+    });
+    assert_eq!(constant.get(g.now()), &10);
+    assert_eq!(crx.get(g.now()), &20);
+
+    var.set(&g, 2);
+    g.recompute();
+    g.recompute();
+    assert_eq!(crx.get(g.now()), &20);
+    assert_eq!(computes.get(), 1);
+}
+
+#[test]
+fn test_link_vars() {
+    let recomputes_b = Cell::new(0);
+    let mut g = RxDAG::new();
+    let a = g.new_var(1);
+    let b = g.new_var(1);
+    g.link_vars(a, b);
+    let recomputes_b_ref = &recomputes_b;
+    g.run_crx(move |g| {
+        b.get(g);
+        recomputes_b_ref.set(recomputes_b_ref.get() + 1);
+    });
+
+    a.set(&g, 2);
+    g.recompute();
+    // The link set `b` from inside the recompute
+    assert!(g.is_dirty());
+    g.recompute();
+    assert!(!g.is_dirty());
+    assert_eq!(a.get(g.now()), &2);
+    assert_eq!(b.get(g.now()), &2);
+
+    b.set(&g, 3);
+    g.recompute();
+    g.recompute();
+    assert_eq!(a.get(g.now()), &3);
+    assert_eq!(b.get(g.now()), &3);
+
+    // Converges instead of oscillating, even if both are set
+    a.set(&g, 4);
+    b.set(&g, 5);
+    for _ in 0..3 {
+        g.recompute();
+    }
+    assert_eq!(a.get(g.now()), &4);
+    assert_eq!(b.get(g.now()), &4);
+    let recomputes = recomputes_b.get();
+    for _ in 0..3 {
+        g.recompute();
+    }
+    assert_eq!(recomputes_b.get(), recomputes);
+}
+
+#[test]
+fn test_scope() {
+    struct Model<'c> {
+        width: Var<'c, u32>,
+        height: Var<'c, u32>,
+        area: CRx<'c, u32>
+    }
+
+    let (mut g, model) = RxDAG::scope(|g| {
+        let width = g.new_var(2);
+        let height = g.new_var(3);
+        let area = g.new_crx(move |g| width.get(g) * height.get(g));
+        Model { width, height, area }
+    });
+    assert_eq!(*model.area.get(g.now()), 6);
+    model.width.set(&g, 4);
+    model.height.set(&g, 5);
+    assert_eq!(*model.area.get(g.now()), 20);
+}
+
+#[test]
+fn test_incremental_crx() {
+    let num_adds = Cell::new(0);
+    let num_removes = Cell::new(0);
+    let mut g = RxDAG::new();
+    let source = g.new_var(vec![1, 2, 3]);
+    let sum = g.new_incremental_crx(source, 0, |sum, x| {
+        num_adds.set(num_adds.get() + 1);
+        *sum += x;
+    }, |sum, x| {
+        num_removes.set(num_removes.get() + 1);
+        *sum -= x;
+    });
+    assert_eq!(*sum.get(g.now()), 6);
+    assert_eq!(num_adds.get(), 3);
+
+    source.modify(&g, |xs| {
+        let mut xs = xs.clone();
+        xs.push(4);
+        xs
+    });
+    assert_eq!(*sum.get(g.now()), 10);
+    assert_eq!(num_adds.get(), 4);
+    assert_eq!(num_removes.get(), 0);
+
+    source.set(&g, vec![1, 5, 3]);
+    assert_eq!(*sum.get(g.now()), 9);
+    assert_eq!(num_adds.get(), 5);
+    assert_eq!(num_removes.get(), 2);
+}
+
+#[test]
+fn test_would_recompute() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    assert!(!g.would_recompute());
+    var.set(&g, 2);
+    assert!(g.would_recompute());
+    g.recompute();
+    assert!(!g.would_recompute());
+    assert_eq!(*crx.get(g.now()), 4);
+    *g.get_mut(var) = 3;
+    assert!(g.would_recompute());
+    assert_eq!(*crx.get(g.now()), 6);
+    assert!(!g.would_recompute());
+}
+
+reactive_impl! {
+    struct Sums<'c> {
+        a: i32,
+        b: i32
+    }
+    fn new;
+    computed {
+        fn sum(a, b) -> i32 { a + b }
+        fn double_sum(sum) -> i32 { sum * 2 }
+    }
+}
+
+#[test]
+fn test_reactive_impl() {
+    let mut g = RxDAG::new();
+    let sums = Sums::new(&g, 1, 2);
+    assert_eq!(*sums.sum.get(g.now()), 3);
+    assert_eq!(*sums.double_sum.get(g.now()), 6);
+    sums.a.set(&g, 5);
+    assert_eq!(*sums.sum.get(g.now()), 7);
+    assert_eq!(*sums.double_sum.get(g.now()), 14);
+    sums.b.set(&g, -5);
+    assert_eq!(*sums.sum.get(g.now()), 0);
+}
+
+#[test]
+fn test_crx_group() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let group = g.new_crx_vec(move |g| {
+        let x = *var.get(g);
+        vec![x, x * 2, x * 3]
+    });
+    assert_eq!(group.members().len(), 3);
+    assert_eq!(group.get_all(g.now()), vec![&1, &2, &3]);
+    var.set(&g, 2);
+    assert_eq!(group.get_all(g.now()), vec![&2, &4, &6]);
+
+    let array_group = CRxGroup::from(g.new_crx_array(move |g| [*var.get(g) + 1, *var.get(g) + 2]));
+    assert_eq!(array_group.get_all(g.now()), vec![&3, &4]);
+}
+
+#[test]
+fn test_map2() {
+    struct NotClone(i32);
+
+    let mut g = RxDAG::new();
+    let a = g.new_var(NotClone(2));
+    let b = g.new_var("x");
+    let crx = g.map2(a, b, |a, b| b.repeat(a.0 as usize));
+    assert_eq!(crx.get(g.now()), "xx");
+    a.set(&g, NotClone(3));
+    assert_eq!(crx.get(g.now()), "xxx");
+    b.set(&g, "y");
+    assert_eq!(crx.get(g.now()), "yyy");
+
+    let sum = g.map3(a, crx, g.new_var(10), |a, crx, c| a.0 + crx.len() as i32 + c);
+    assert_eq!(*sum.get(g.now()), 16);
+}
+
+#[test]
+fn test_run_crx_skip_initial() {
+    let side_effect = Cell::new(0);
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    g.run_crx_skip_initial(move |g| *var.get(g), |value| side_effect.set(value));
+    assert_eq!(side_effect.get(), 0);
+    g.recompute();
+    assert_eq!(side_effect.get(), 0);
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(side_effect.get(), 2);
+    var.set(&g, 3);
+    g.recompute();
+    assert_eq!(side_effect.get(), 3);
+}
+
+/// Counts how many times it's cloned in `clones`, to check that values aren't cloned needlessly
+#[derive(Debug, PartialEq)]
+struct CountsClones<'a, T> {
+    value: T,
+    clones: &'a Cell<usize>
+}
+
+impl<'a, T: Clone> Clone for CountsClones<'a, T> {
+    fn clone(&self) -> Self {
+        self.clones.set(self.clones.get() + 1);
+        CountsClones {
+            value: self.value.clone(),
+            clones: self.clones
+        }
+    }
+}
+
+#[test]
+fn test_derive_transaction() {
+    let num_clones = Cell::new(0);
+    let mut g = RxDAG::new();
+    let rx = g.new_var(CountsClones { value: vec![1, 2, 3], clones: &num_clones });
+    let drxs = (0..3).map(|index| rx.derive_using_clone(move |x| &x.value[index], move |x, new| {
+        x.value[index] = new;
+    })).collect::<Vec<_>>();
+    rx.derive_transaction(&g, |txn| {
+        txn.set(&drxs[0], 2);
+        txn.set(&drxs[1], 3);
+        txn.set(&drxs[2], 4);
+    });
+    assert_eq!(num_clones.get(), 1);
+    assert_eq!(rx.get(g.now()).value, vec![2, 3, 4]);
+    assert_eq!(drxs[1].get(g.now()), &3);
+}
+
+#[test]
+fn test_assert_recomputed() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let other = g.new_var(0);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let other_crx = g.new_crx(move |g| *other.get(g) + 1);
+    var.set(&g, 2);
+    g.recompute();
+    g.assert_recomputed(var.raw().raw());
+    g.assert_recomputed(crx.raw().raw());
+    g.assert_not_recomputed(other.raw().raw());
+    g.assert_not_recomputed(other_crx.raw().raw());
+
+    g.recompute();
+    g.assert_not_recomputed(crx.raw().raw());
+    let result = catch_unwind(AssertUnwindSafe(|| g.assert_recomputed(crx.raw().raw())));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_dynamic_crx() {
+    let mut g = RxDAG::new();
+    let cells = (0..4).map(|i| g.new_var(i * 10).raw().raw()).collect::<Vec<_>>();
+    let formula = g.new_var(vec![0, 1]);
+    let cells2 = cells.clone();
+    let sum = unsafe {
+        g.new_dynamic_crx::<i32, _>(move |g, read| {
+            formula.get(g).iter().map(|&cell| *read(cells2[cell])).sum::<i32>()
+        })
+    };
+    assert_eq!(*sum.get(g.now()), 10);
+    unsafe { Var::<i32>::from_raw(RxRef::from_raw(cells[1])) }.set(&g, 11);
+    assert_eq!(*sum.get(g.now()), 11);
+
+    formula.set(&g, vec![2, 3]);
+    assert_eq!(*sum.get(g.now()), 50);
+    // No longer a dependency
+    unsafe { Var::<i32>::from_raw(RxRef::from_raw(cells[1])) }.set(&g, 12);
+    g.recompute();
+    g.assert_not_recomputed(sum.raw().raw());
+    unsafe { Var::<i32>::from_raw(RxRef::from_raw(cells[3])) }.set(&g, 40);
+    assert_eq!(*sum.get(g.now()), 60);
+}
+
+#[test]
+fn test_swap() {
+    let mut g = RxDAG::new();
+    let a = g.new_var(String::from("a"));
+    let b = g.new_var(String::from("b"));
+    let a_crx = g.new_crx(move |g| format!("{}!", a.get(g)));
+    let b_crx = g.new_crx(move |g| format!("{}?", b.get(g)));
+    g.swap(a, b);
+    g.recompute();
+    g.assert_recomputed(a_crx.raw().raw());
+    g.assert_recomputed(b_crx.raw().raw());
+    assert_eq!(a.get(g.stale()), "b");
+    assert_eq!(b.get(g.stale()), "a");
+    assert_eq!(a_crx.get(g.stale()), "b!");
+    assert_eq!(b_crx.get(g.stale()), "a?");
+
+    // Swaps pending values
+    a.set(&g, String::from("c"));
+    g.swap(a, b);
+    assert_eq!(a.get(g.now()), "a");
+    assert_eq!(b.get(g.now()), "c");
+
+    g.swap(a, a);
+    assert_eq!(a.get(g.now()), "a");
+}
+
+#[test]
+fn test_var_initial() {
+    let mut g = RxDAG::new();
+    let var = g.new_var_with_initial(vec![1, 2]);
+    let crx = g.new_crx(move |g| var.get(g).len());
+    let other = g.new_var(0);
+    var.set(&g, vec![3]);
+    assert_eq!(*crx.get(g.now()), 1);
+    assert_eq!(var.get(g.now()), &vec![3]);
+    assert_eq!(var.initial(g.now()), Some(&vec![1, 2]));
+    *g.get_mut(var) = vec![4, 5, 6];
+    assert_eq!(*crx.get(g.now()), 3);
+    assert_eq!(var.initial(g.now()), Some(&vec![1, 2]));
+    assert_eq!(other.initial(g.now()), None);
+}
+
+#[test]
+fn test_suspend() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    g.suspend();
+    assert!(g.is_suspended());
+    var.set(&g, 2);
+    assert_eq!(*crx.get(g.now()), 2);
+    var.modify(&g, |x| x + 1);
+    assert_eq!(var.get(g.now()), &1);
+    g.resume_and_recompute();
+    assert!(!g.is_suspended());
+    assert_eq!(*crx.get(g.stale()), 6);
+    var.set(&g, 4);
+    assert_eq!(*crx.get(g.now()), 8);
+}
+
+#[test]
+fn test_split_result() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(5);
+    let result = g.new_crx(move |g| {
+        let x = *var.get(g);
+        if x >= 0 { Ok(x) } else { Err(format!("{} is negative", x)) }
+    });
+    let (ok, err) = result.split_result(&g);
+    assert_eq!(ok.get(g.now()), &Some(5));
+    assert_eq!(err.get(g.now()), &None);
+    var.set(&g, -1);
+    assert_eq!(ok.get(g.now()), &None);
+    assert_eq!(err.get(g.now()).as_deref(), Some("-1 is negative"));
+}
+
+#[test]
+fn test_crx_hash_dedup() {
+    let num_recomputes = Cell::new(0);
+    let mut g = RxDAG::new();
+    let var = g.new_var(3);
+    let big = g.new_crx_hash_dedup(move |g| vec![*var.get(g) / 2; 1000]);
+    let num_recomputes_ref = &num_recomputes;
+    g.run_crx(move |g| {
+        big.get(g);
+        num_recomputes_ref.set(num_recomputes_ref.get() + 1);
+    });
+    assert_eq!(num_recomputes.get(), 1);
+    // Same value
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(num_recomputes.get(), 1);
+    var.set(&g, 4);
+    g.recompute();
+    assert_eq!(num_recomputes.get(), 2);
+    assert_eq!(big.get(g.now())[999], 2);
+}
+
+#[test]
+fn test_read_then_set() {
+    let mut g = RxDAG::new();
+    let counter = g.new_var(0);
+    let step = g.new_var(2);
+    for _ in 0..3 {
+        g.read_then_set(|g| counter.get(g.stale()) + step.get(g.stale()), |g, next| counter.set(g, next));
+        g.recompute();
+    }
+    assert_eq!(counter.get(g.now()), &6);
+}
+
+#[test]
+fn test_recompute_tag() {
+    let effect1 = Cell::new(0);
+    let effect2 = Cell::new(0);
+    let untagged = Cell::new(0);
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let (effect1_ref, effect2_ref, untagged_ref) = (&effect1, &effect2, &untagged);
+    g.run_crx_tagged(1, move |g| effect1_ref.set(*crx.get(g)));
+    g.run_crx_tagged(2, move |g| effect2_ref.set(*crx.get(g)));
+    g.run_crx(move |g| untagged_ref.set(*crx.get(g)));
+    assert_eq!((effect1.get(), effect2.get(), untagged.get()), (2, 2, 2));
+
+    var.set(&g, 2);
+    g.recompute_tag(1);
+    assert_eq!(*crx.get(g.stale()), 4);
+    assert_eq!((effect1.get(), effect2.get(), untagged.get()), (4, 2, 2));
+    // Postponed side-effects run on the next recompute, even if nothing changed
+    g.recompute();
+    assert_eq!((effect1.get(), effect2.get(), untagged.get()), (4, 4, 4));
+}
+
+#[test]
+fn test_recompute_tag_on_transition() {
+    let transitions = RefCell::new(Vec::new());
+    let transitions_ref = &transitions;
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 10);
+    crx.on_transition(&g, move |old, new| transitions_ref.borrow_mut().push((*old, *new)));
+
+    // Transitions need the old value, so they aren't postponed
+    var.set(&g, 2);
+    g.recompute_tag(1);
+    assert_eq!(*transitions.borrow(), vec![(10, 20)]);
+    var.set(&g, 3);
+    assert_eq!(g.evaluate(crx), 30);
+    assert_eq!(*transitions.borrow(), vec![(10, 20), (20, 30)]);
+    g.recompute();
+    assert_eq!(*transitions.borrow(), vec![(10, 20), (20, 30)]);
+}
+
+#[test]
+fn test_set_and_now() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let snapshot = g.set_and_now(var, 5);
+    assert_eq!(var.get(snapshot), &5);
+    assert_eq!(*crx.get(snapshot), 10);
+}
+
+#[test]
+fn test_aggregates() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let inputs = [
+        g.new_crx(move |g| *var.get(g)),
+        g.new_crx(move |g| *var.get(g) * 10),
+        g.new_crx(move |g| 5 - *var.get(g))
+    ];
+    let sum = g.sum_of(&inputs);
+    let max = g.max_of(&inputs);
+    let min = g.min_of(&inputs);
+    assert_eq!(*sum.get(g.now()), 15);
+    assert_eq!(*max.get(g.now()), Some(10));
+    assert_eq!(*min.get(g.now()), Some(1));
+    var.set(&g, -1);
+    assert_eq!(*sum.get(g.now()), -5);
+    assert_eq!(*max.get(g.now()), Some(6));
+    assert_eq!(*min.get(g.now()), Some(-10));
+
+    let empty_sum = g.sum_of::<i32>(&[]);
+    let empty_max = g.max_of::<i32>(&[]);
+    assert_eq!(*empty_sum.get(g.now()), 0);
+    assert_eq!(*empty_max.get(g.now()), None);
+}
+
+#[test]
+fn test_after_next_recompute() {
+    let runs = Cell::new(0);
+    let runs_ref = &runs;
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    g.after_next_recompute(move || runs_ref.set(runs_ref.get() + 1));
+    assert_eq!(runs.get(), 0);
+    g.recompute();
+    assert_eq!(runs.get(), 1);
+    var.set(&g, 2);
+    g.recompute();
+    g.recompute();
+    assert_eq!(runs.get(), 1);
+}
+
+#[test]
+fn test_build_operators() {
+    let mut g = RxDAG::new();
+    let c = g.new_var(4);
+    let (a, result) = g.build(|b| {
+        let a = b.var(1);
+        let result = (a + b.var(2)) * b.wrap(c.raw()) - b.var(2);
+        (a.raw(), result.raw())
+    });
+    assert_eq!(*result.get(g.now()), 10);
+    assert_eq!(*a.get(g.now()), 1);
+    c.set(&g, 10);
+    assert_eq!(*result.get(g.now()), 28);
+    let half = g.build(|b| b.wrap(result) / b.var(2)).raw();
+    assert_eq!(*half.get(g.now()), 14);
+}
+
+#[test]
+fn test_rising_edge() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(0);
+    let crx = g.new_crx(move |g| *var.get(g));
+    let crossed = crx.rising_edge(&g, |value| *value > 10);
+    assert!(!*crossed.get(g.now()));
+    var.set(&g, 5);
+    assert!(!*crossed.get(g.now()));
+    var.set(&g, 15);
+    assert!(*crossed.get(g.now()));
+    // Only true for one recompute, even if nothing changes
+    assert!(!*crossed.get(g.now()));
+    var.set(&g, 20);
+    assert!(!*crossed.get(g.now()));
+    var.set(&g, 0);
+    assert!(!*crossed.get(g.now()));
+    var.set(&g, 11);
+    assert!(*crossed.get(g.now()));
+    var.set(&g, 12);
+    assert!(!*crossed.get(g.now()));
+}
+
+#[test]
+fn test_apply_pending() {
+    let mut g = RxDAG::new();
+    let a = g.new_var(1);
+    let b = g.new_var(String::from("b"));
+    let crx = g.new_crx(move |g| format!("{}{}", a.get(g), b.get(g)));
+    let sets = std::thread::scope(|scope| scope.spawn(move || {
+        let mut sets = PendingSets::new();
+        sets.push(a, 2);
+        sets.push(b, String::from("c"));
+        sets.push(a, 3);
+        sets
+    }).join().unwrap());
+    assert_eq!(sets.len(), 3);
+    g.apply_pending(sets);
+    assert_eq!(crx.get(g.stale()), "1b");
+    assert_eq!(crx.get(g.now()), "3c");
+}
+
+#[test]
+fn test_crx_uninit() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+    let mut g = RxDAG::new();
+    let var = g.new_var(2);
+    let crx = g.new_crx_uninit(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        *var.get(g) * 10
+    });
+    assert_eq!(computes.get(), 0);
+    assert_eq!(*crx.get(g.stale()), 0);
+    assert_eq!(*crx.get(g.now()), 20);
+    assert_eq!(computes.get(), 1);
+    g.recompute();
+    assert_eq!(computes.get(), 1);
+    var.set(&g, 3);
+    assert_eq!(*crx.get(g.now()), 30);
+    assert_eq!(computes.get(), 2);
+}
+
+#[test]
+fn test_derive_using_cow() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32
+    }
+
+    let clones = Cell::new(0);
+    let mut g = RxDAG::new();
+    let var = g.new_var(CountsClones { value: Person { name: String::from("a"), age: 1 }, clones: &clones });
+    let age = var.derive_using_cow(|root| &root.value.age, |root, age| {
+        if root.value.age != age {
+            root.to_mut().value.age = age;
+        }
+    });
+
+    age.set_if_changed(&g, 1);
+    // Nothing was set, so the graph isn't dirty
+    g.assert_settled();
+    g.recompute();
+    assert_eq!(clones.get(), 0);
+    g.assert_not_recomputed(var.raw().raw());
+
+    age.set_if_changed(&g, 2);
+    g.recompute();
+    assert_eq!(clones.get(), 1);
+    g.assert_recomputed(var.raw().raw());
+    assert_eq!(*age.get(g.now()), 2);
+
+    // Pending value is kept
+    var.set(&g, CountsClones { value: Person { name: String::from("b"), age: 3 }, clones: &clones });
+    age.set_if_changed(&g, 3);
+    assert_eq!(clones.get(), 1);
+    assert_eq!(var.get(g.now()).value.name, "b");
+}
+
+#[test]
+fn test_evaluate() {
+    let unrelated_computes = Cell::new(0);
+    let unrelated_computes_ref = &unrelated_computes;
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let other_var = g.new_var(1);
+    let mut deep = g.new_crx(move |g| *var.get(g) + 1);
+    for _ in 0..5 {
+        deep = g.new_crx(move |g| *deep.get(g) * 2);
+    }
+    let unrelated = g.new_crx(move |g| {
+        unrelated_computes_ref.set(unrelated_computes_ref.get() + 1);
+        *var.get(g) + *other_var.get(g)
+    });
+    assert_eq!(unrelated_computes.get(), 1);
+
+    var.set(&g, 2);
+    other_var.set(&g, 10);
+    g.after_next_recompute(move || unrelated_computes_ref.set(unrelated_computes_ref.get() + 100));
+    assert_eq!(g.evaluate(deep), 96);
+    assert_eq!(*unrelated.get(g.stale()), 2);
+    assert_eq!(*other_var.get(g.stale()), 1);
+    assert_eq!(unrelated_computes.get(), 101);
+    assert!(g.is_dirty());
+
+    // The next recompute updates what's stale, even though `var` already changed
+    assert_eq!(*unrelated.get(g.now()), 12);
+    assert_eq!(unrelated_computes.get(), 102);
+    assert_eq!(*deep.get(g.now()), 96);
+}
+
+#[test]
+fn test_on_set() {
+    let sets = RefCell::new(Vec::new());
+    let sets_ref = &sets;
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx_hash_dedup(move |g| *var.get(g) > 0);
+    var.on_set(&g, move |value| sets_ref.borrow_mut().push(*value));
+    var.set(&g, 2);
+    var.modify(&g, |value| value + 1);
+    assert_eq!(*sets.borrow(), vec![2, 3]);
+    assert_eq!(*var.get(g.stale()), 1);
+    g.recompute();
+    // Fires even though the dependent doesn't change
+    var.set(&g, 4);
+    g.recompute();
+    g.assert_not_recomputed(crx.raw().raw());
+    assert_eq!(*sets.borrow(), vec![2, 3, 4]);
+
+    // A variable of another graph at the same index doesn't call the listener
+    let g2 = RxDAG::new();
+    let other = g2.new_var("a");
+    g.set_mismatch_policy(MismatchPolicy::None);
+    let _ = catch_unwind(AssertUnwindSafe(|| other.set(&g, "b")));
+    let _ = catch_unwind(AssertUnwindSafe(|| other.modify(&g, |_| "c")));
+    assert_eq!(*sets.borrow(), vec![2, 3, 4]);
+}
+
+#[test]
+fn test_pull_crx() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_pull_crx(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        *var.get(g) * 2
+    });
+    g.recompute();
+    assert_eq!(computes.get(), 0);
+    assert_eq!(*crx.get(g.now()), 2);
+    assert_eq!(*crx.get(g.now()), 2);
+    assert_eq!(computes.get(), 1);
+    for value in 2..5 {
+        var.set(&g, value);
+        g.recompute();
+    }
+    assert_eq!(computes.get(), 1);
+    assert_eq!(*crx.get(g.now()), 8);
+    assert_eq!(computes.get(), 2);
+    g.recompute();
+    assert_eq!(*crx.get(g.now()), 8);
+    assert_eq!(computes.get(), 2);
+}
+
+#[test]
+fn test_alias_crx() {
+    // Not Clone
+    #[derive(Debug, PartialEq)]
+    struct Big(Vec<i32>);
+
+    let mut g = RxDAG::new();
+    let left = g.new_var(Big(vec![1, 2]));
+    let right = g.new_crx(move |g| Big(left.get(g).0.iter().map(|x| x * 10).collect()));
+    let use_right = g.new_var(false);
+    let alias = g.new_alias_crx(move |g| if *use_right.get(g) { right.raw() } else { left.raw() });
+    assert_eq!(alias.get(g.now()), &Big(vec![1, 2]));
+    assert!(std::ptr::eq(alias.get(g.now()), left.get(g.now())));
+    use_right.set(&g, true);
+    assert_eq!(alias.get(g.now()), &Big(vec![10, 20]));
+    assert!(std::ptr::eq(alias.get(g.now()), right.get(g.now())));
+    left.set(&g, Big(vec![3]));
+    assert_eq!(alias.get(g.now()), &Big(vec![30]));
+}
+
+#[test]
+fn test_alias_crx_of_cow() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(vec![4, 5]);
+    let flag = g.new_var(true);
+    let cow = g.new_crx_cow(move |g| if *flag.get(g) {
+        Cow::Owned(vec![1, 2, 3])
+    } else {
+        Cow::Borrowed(var.get(g))
+    });
+    let alias = g.new_alias_crx(move |_| cow.raw());
+    assert_eq!(alias.get(g.now()), &vec![1, 2, 3]);
+
+    // The alias reads through `cow` even before it recomputes
+    flag.set(&g, false);
+    {
+        let mut stepper = g.recompute_stepper();
+        for _ in 0..4 {
+            stepper.step();
+        }
+        assert_eq!(alias.get(stepper.snapshot()), &vec![4, 5]);
+    }
+    var.set(&g, vec![6]);
+    assert_eq!(alias.get(g.now()), &vec![6]);
+}
+
+#[test]
+fn test_max_elements() {
+    let mut g = RxDAG::with_max_elements(4);
+    let var = g.try_new_var(1).unwrap();
+    let crx = g.try_new_crx(move |g| *var.get(g) + 1).unwrap();
+    let var2 = g.try_new_var(2).unwrap();
+    assert_eq!(g.try_new_crx(move |g| *var2.get(g) + *crx.get(g)).unwrap_err(), CapacityExceeded { max: 4 });
+    assert_eq!(g.try_new_var(3).unwrap_err().to_string(), "RxDAG exceeded its maximum of 4 elements");
+    assert!(catch_unwind(AssertUnwindSafe(|| g.new_var(3))).is_err());
+    var.set(&g, 5);
+    assert_eq!(*crx.get(g.now()), 6);
+    assert_eq!(g.shape().0.len(), 4);
+}
+
+#[test]
+fn test_poll_changed() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let unrelated = g.new_var(0);
+    let mut since = 0;
+    assert_eq!(crx.poll_changed(g.now(), &mut since), Some(2));
+    assert_eq!(crx.poll_changed(g.now(), &mut since), None);
+    unrelated.set(&g, 1);
+    assert_eq!(crx.poll_changed(g.now(), &mut since), None);
+    let before = since;
+    var.set(&g, 5);
+    assert_eq!(crx.poll_changed(g.now(), &mut since), Some(10));
+    assert!(since > before);
+    assert_eq!(crx.poll_changed(g.now(), &mut since), None);
+}
+
+#[test]
+fn test_external_input() {
+    let mut g = RxDAG::new();
+    let (var, sink) = g.new_external_input(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    std::thread::spawn(move || {
+        assert!(sink.push(2));
+        assert!(sink.push(3));
+    }).join().unwrap();
+    assert_eq!(*crx.get(g.stale()), 2);
+    assert_eq!(*crx.get(g.now()), 6);
+    assert_eq!(*var.get(g.now()), 3);
+
+    let (_, sink) = g.new_external_input(0);
+    drop(g);
+    assert!(!sink.push(1));
+}
+
+#[test]
+fn test_windowed_crx() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(3.0);
+    let average = g.new_windowed_crx(var, 3, |values| values.iter().sum::<f64>() / values.len() as f64);
+    assert_eq!(*average.get(g.now()), 3.0);
+    var.set(&g, 6.0);
+    assert_eq!(*average.get(g.now()), 4.5);
+    var.set(&g, 9.0);
+    assert_eq!(*average.get(g.now()), 6.0);
+    var.set(&g, 12.0);
+    assert_eq!(*average.get(g.now()), 9.0);
+    // No change, so the window doesn't move
+    g.recompute();
+    assert_eq!(*average.get(g.now()), 9.0);
+    // Rerunning without a change doesn't move it either
+    g.rediscover_deps(average.raw().raw());
+    g.recompute();
+    assert_eq!(*average.get(g.now()), 9.0);
+    var.set(&g, 0.0);
+    assert_eq!(*average.get(g.now()), 7.0);
+}
+
+#[test]
+fn test_clear_changed_flags() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    var.set(&g, 2);
+    g.recompute();
+    g.assert_recomputed(var.raw().raw());
+    g.assert_recomputed(crx.raw().raw());
+    g.clear_changed_flags();
+    g.assert_not_recomputed(var.raw().raw());
+    g.assert_not_recomputed(crx.raw().raw());
+    assert_eq!(*crx.get(g.stale()), 4);
+
+    var.set(&g, 3);
+    g.clear_changed_flags();
+    g.recompute();
+    g.assert_recomputed(crx.raw().raw());
+    assert_eq!(*crx.get(g.stale()), 6);
+}
+
+#[test]
+fn test_derive_transaction_checked() {
+    let mut g = RxDAG::new();
+    let range = g.new_var((0, 10));
+    let start = range.derive_using_clone(|x| &x.0, |x, new| x.0 = new);
+    let end = range.derive_using_clone(|x| &x.1, |x, new| x.1 = new);
+    let validate = |range: &(i32, i32)| if range.0 <= range.1 { Ok(()) } else { Err("start after end") };
+
+    // The intermediate state (20, 10) is invalid, but the result isn't
+    assert_eq!(range.derive_transaction_checked(&g, |txn| {
+        txn.set(&start, 20);
+        txn.set(&end, 30);
+    }, validate), Ok(()));
+    assert_eq!(*range.get(g.now()), (20, 30));
+
+    assert_eq!(range.derive_transaction_checked(&g, |txn| {
+        txn.set(&end, 25);
+        txn.set(&start, 40);
+    }, validate), Err("start after end"));
+    g.assert_settled();
+    g.recompute();
+    g.assert_not_recomputed(range.raw().raw());
+    assert_eq!(*range.get(g.now()), (20, 30));
+}
+
+#[test]
+fn test_recompute_if_elapsed() {
+    use std::time::{Duration, Instant};
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let start = Instant::now();
+    let interval = Duration::from_millis(100);
+    var.set(&g, 2);
+    assert!(g.recompute_if_elapsed_at(interval, start));
+    assert_eq!(*var.get(g.stale()), 2);
+    var.set(&g, 3);
+    assert!(!g.recompute_if_elapsed_at(interval, start + Duration::from_millis(50)));
+    var.set(&g, 4);
+    assert_eq!(*var.get(g.stale()), 2);
+    assert!(g.recompute_if_elapsed_at(interval, start + Duration::from_millis(100)));
+    assert_eq!(*var.get(g.stale()), 4);
+}
+
+#[test]
+fn test_inspect_pending() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    var.inspect_pending(&g, |pending| {
+        assert_eq!(pending, None);
+        None
+    });
+    g.assert_settled();
+    var.set(&g, 2);
+    var.inspect_pending(&g, |pending| pending.map(|x| x * 10));
+    g.recompute();
+    assert_eq!(*var.get(g.stale()), 20);
+
+    var.set(&g, 3);
+    var.inspect_pending(&g, |pending| {
+        assert_eq!(pending, Some(&3));
+        None
+    });
+    g.recompute();
+    g.assert_not_recomputed(var.raw().raw());
+    assert_eq!(*var.get(g.stale()), 20);
+}
+
+#[test]
+fn test_hash_map_entry() {
+    use std::collections::HashMap;
+
+    let mut g = RxDAG::new();
+    let map = g.new_var(HashMap::from([("a", 1), ("b", 2)]));
+    let a = map.entry("a");
+    let c = map.entry("c");
+    let sum = g.new_crx(move |g| a.get(g).copied().unwrap_or(0) + c.get(g).copied().unwrap_or(0));
+    assert_eq!(*sum.get(g.now()), 1);
+
+    a.set(&g, Some(10));
+    c.set(&g, Some(5));
+    assert_eq!(*sum.get(g.now()), 15);
+    assert_eq!(c.get(g.now()), Some(&5));
+
+    a.set(&g, None);
+    assert_eq!(a.get(g.now()), None);
+    assert_eq!(*sum.get(g.now()), 5);
+    assert_eq!(map.get(g.now()).len(), 2);
+}
+
+#[test]
+fn test_untracked_scope() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+
+    let mut g = RxDAG::new();
+    let var1 = g.new_var(1);
+    let var2 = g.new_var(2);
+    let var3 = g.new_var(3);
+    let crx = g.new_crx(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        let value1 = *var1.get(g);
+        let (value1_again, value2) = g.untracked_scope(|| (*var1.get(g), *var2.get(g)));
+        value1 + value1_again + value2 + *var3.get(g)
+    });
+    assert_eq!(crx.get(g.now()), &7);
+    assert_eq!(computes.get(), 1);
+
+    // var2 was only read inside the scope, so it isn't a dependency
+    var2.set(&g, 20);
+    assert_eq!(crx.get(g.now()), &7);
+    assert_eq!(computes.get(), 1);
+
+    var1.set(&g, 10);
+    assert_eq!(crx.get(g.now()), &43);
+    assert_eq!(computes.get(), 2);
+
+    var3.set(&g, 30);
+    assert_eq!(crx.get(g.now()), &70);
+    assert_eq!(computes.get(), 3);
+}
+
+#[test]
+fn test_self_ref() {
+    let self_ref = Cell::new(None);
+    let self_ref_ref = &self_ref;
+    let effect_refs = RefCell::new(Vec::new());
+    let effect_refs_ref = &effect_refs;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let _other = g.new_crx(move |g| *var.get(g) + 1);
+    let crx = g.new_crx(move |g| {
+        self_ref_ref.set(g.self_ref());
+        *var.get(g) * 2
+    });
+    assert!(self_ref.get().unwrap().same_node(crx.raw().raw()));
+
+    var.set(&g, 5);
+    g.recompute();
+    let self_ref = self_ref.get().unwrap();
+    assert!(self_ref.same_node(crx.raw().raw()));
+    assert_eq!(*unsafe { RxRef::<i32>::from_raw(self_ref) }.get(g.now()), 10);
+
+    // Side-effects and views aren't nodes
+    g.run_crx(move |g| {
+        var.get(g);
+        effect_refs_ref.borrow_mut().push(g.self_ref());
+    });
+    var.set(&g, 6);
+    g.recompute();
+    assert!(effect_refs.borrow().iter().all(Option::is_none));
+    assert_eq!(effect_refs.borrow().len(), 2);
+    assert!(g.now().as_of(1).self_ref().is_none());
+}
+
+#[test]
+fn test_read_many() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crxs = [
+        g.new_crx(move |g| *var.get(g) + 1),
+        g.new_crx(move |g| *var.get(g) * 10),
+        g.new_crx(move |g| -*var.get(g))
+    ];
+    assert_eq!(g.now().read_many(&crxs), vec![2, 10, -1]);
+    var.set(&g, 3);
+    assert_eq!(g.now().read_many(&crxs), vec![4, 30, -3]);
+    assert_eq!(g.now().read_many::<i32>(&[]), Vec::<i32>::new());
+}
+
+#[test]
+fn test_assert_settled() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let _crx = g.new_crx(move |g| *var.get(g) + 1);
+    g.assert_settled();
+
+    var.set(&g, 2);
+    assert!(catch_unwind(AssertUnwindSafe(|| g.assert_settled())).is_err());
+    g.recompute();
+    g.assert_settled();
+}
+
+#[test]
+fn test_run_crx_distinct() {
+    let received = RefCell::new(Vec::new());
+    let received_ref = &received;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g));
+    g.run_crx_distinct(crx, move |value| received_ref.borrow_mut().push(*value));
+    assert_eq!(*received.borrow(), vec![1]);
+
+    for value in [2, 1, 2, 3, 1] {
+        var.set(&g, value);
+        g.recompute();
+    }
+    assert_eq!(*received.borrow(), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_delayed_crx() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let source = g.new_crx(move |g| *var.get(g));
+    let delayed = g.new_delayed_crx(source);
+    assert_eq!(*delayed.get(g.now()), 1);
+
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(*delayed.get(g.stale()), 1);
+    g.recompute();
+    assert_eq!(*delayed.get(g.stale()), 2);
+    g.recompute();
+    assert_eq!(*delayed.get(g.stale()), 2);
+
+    for value in [3, 4, 5] {
+        var.set(&g, value);
+        g.recompute();
+        assert_eq!(*delayed.get(g.stale()), value - 1);
+    }
+    g.recompute();
+    assert_eq!(*delayed.get(g.stale()), 5);
+}
+
+#[test]
+fn test_delayed_crx_cow_source() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let source = g.new_crx_cow(move |g| Cow::Borrowed(var.get(g)));
+    let delayed = g.new_delayed_crx(source);
+    assert_eq!(*delayed.get(g.now()), 1);
+
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(*delayed.get(g.stale()), 1);
+    // The catch-up is pending
+    assert!(g.is_dirty());
+    g.recompute();
+    assert_eq!(*delayed.get(g.stale()), 2);
+    assert!(!g.is_dirty());
+}
+
+#[test]
+fn test_new_crx_strict() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let num_elems = g.stats().total_elements;
+    assert_eq!(g.new_crx_strict(|_| 5).err(), Some(NoInputs));
+    assert_eq!(g.stats().total_elements, num_elems);
+
+    let crx = g.new_crx_strict(move |g| *var.get(g) + 1).unwrap();
+    var.set(&g, 2);
+    assert_eq!(*crx.get(g.now()), 3);
+}
+
+#[test]
+fn test_with_snapshot() {
+    let computes = Cell::new(0);
+    let computes_ref = &computes;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let x = g.new_crx(move |g| {
+        computes_ref.set(computes_ref.get() + 1);
+        *var.get(g) * 2
+    });
+    let y = g.new_crx(move |g| *var.get(g) + 10);
+    var.set(&g, 2);
+    let sum = with_snapshot!(g, |s| {
+        let a = s.read(x);
+        let b = s.read(y);
+        let c = s.read(var);
+        a + b + c
+    });
+    assert_eq!(sum, 18);
+    assert_eq!(computes.get(), 2);
+}
+
+#[test]
+fn test_snapshot_read_while_recomputing() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let other = g.new_var(2);
+    g.run_crx(move |g| {
+        if *var.get(g) > 1 {
+            panic!("side-effect failed");
+        }
+    });
+
+    // A computation panicking out of a recompute doesn't leave the graph recomputing
+    var.set(&g, 2);
+    assert!(catch_unwind(AssertUnwindSafe(|| g.recompute())).is_err());
+    assert_eq!(*other.get(g.stale()), 2);
+
+    // Snapshots are also fine between steps
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    var.set(&g, 3);
+    let mut stepper = g.recompute_stepper();
+    stepper.step();
+    assert_eq!(*var.get(stepper.snapshot()), 3);
+}
+
+#[test]
+fn test_accumulating_crx() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let reset = g.new_var(false);
+    let source = g.new_crx(move |g| *var.get(g));
+    let sum = g.new_accumulating_crx(source, 0, |sum, value| *sum += value, reset);
+    assert_eq!(*sum.get(g.now()), 1);
+
+    var.set(&g, 2);
+    assert_eq!(*sum.get(g.now()), 3);
+    var.set(&g, 3);
+    assert_eq!(*sum.get(g.now()), 6);
+
+    reset.set(&g, true);
+    assert_eq!(*sum.get(g.now()), 0);
+    var.set(&g, 10);
+    assert_eq!(*sum.get(g.now()), 0);
+    reset.set(&g, false);
+    assert_eq!(*sum.get(g.now()), 0);
+
+    var.set(&g, 4);
+    assert_eq!(*sum.get(g.now()), 4);
+    var.set(&g, 5);
+    assert_eq!(*sum.get(g.now()), 9);
+}
+
+#[test]
+fn test_accumulating_crx_cow_source() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let reset = g.new_var(false);
+    let source = g.new_crx_cow(move |g| Cow::Borrowed(var.get(g)));
+    let sum = g.new_accumulating_crx(source, 0, |sum, value| *sum += value, reset);
+    assert_eq!(*sum.get(g.now()), 1);
+
+    var.set(&g, 2);
+    assert_eq!(*sum.get(g.now()), 3);
+    // Evaluating only the source postpones the sum, which still sees the change
+    var.set(&g, 3);
+    assert_eq!(g.evaluate(source), 3);
+    assert_eq!(*sum.get(g.now()), 6);
+}
+
+#[test]
+fn test_crx_retain() {
+    let dependent_computes = Cell::new(0);
+    let dependent_computes_ref = &dependent_computes;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(5);
+    // Rounds down to a multiple of 10, only replacing the value when it's a different multiple
+    let crx = g.new_crx_retain(0, move |g, current| {
+        let rounded = *var.get(g) / 10 * 10;
+        (rounded != *current).then_some(rounded)
+    });
+    let dependent = g.new_crx(move |g| {
+        dependent_computes_ref.set(dependent_computes_ref.get() + 1);
+        *crx.get(g) + 1
+    });
+    assert_eq!(*crx.get(g.now()), 0);
+    assert_eq!(dependent_computes.get(), 1);
+
+    var.set(&g, 7);
+    g.recompute();
+    g.assert_not_recomputed(crx.raw().raw());
+    assert_eq!(*crx.get(g.stale()), 0);
+    assert_eq!(dependent_computes.get(), 1);
+
+    var.set(&g, 23);
+    g.recompute();
+    g.assert_recomputed(crx.raw().raw());
+    assert_eq!(*dependent.get(g.stale()), 21);
+    assert_eq!(dependent_computes.get(), 2);
+}
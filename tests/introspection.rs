@@ -0,0 +1,505 @@
+use mini_rx::*;
+use test_log::test;
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn test_shape() {
+    fn build<'c>(g: &RxDAG<'c>) -> (Var<'c, i32>, CRx<'c, i32>) {
+        let var = g.new_var(1);
+        let var2 = g.new_var(2);
+        let crx = g.new_crx(move |g| *var.get(g) * 2);
+        let (crx2, _crx3) = g.new_crx2(move |g| (*crx.get(g) + *var2.get(g), *var.get(g)));
+        (var, crx2)
+    }
+
+    let g = RxDAG::new();
+    build(&g);
+    let shape = g.shape();
+    assert_eq!(shape.0, vec![
+        ElemShape::Var,
+        ElemShape::Var,
+        ElemShape::Edge { input_backwards_offsets: vec![2], num_outputs: 1 },
+        ElemShape::CRx,
+        ElemShape::Edge { input_backwards_offsets: vec![4, 3, 1], num_outputs: 2 },
+        ElemShape::CRx,
+        ElemShape::CRx
+    ]);
+
+    let (mut g2, (var, crx2)) = RxDAG::from_shape(&shape, build).unwrap();
+    assert_eq!(g2.shape(), shape);
+    var.set(&g2, 2);
+    assert_eq!(crx2.get(g2.now()), &6);
+
+    let mismatch = RxDAG::from_shape(&shape, |g| {
+        let var = g.new_var(1);
+        g.new_crx(move |g| *var.get(g) * 2)
+    }).unwrap_err();
+    assert_eq!(mismatch.expected, shape);
+    assert_eq!(mismatch.actual.0.len(), 3);
+}
+
+#[test]
+fn test_transitive_dependents() {
+    let g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    let other = g.new_var(0);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    let crx3 = g.new_crx(move |g| crx2.get(g).to_string());
+    let other_crx = g.new_crx(move |g| *other.get(g) + 1);
+
+    let dependents = g.transitive_dependents(rx.raw().raw());
+    assert_eq!(dependents.len(), 3);
+    assert!(dependents[0].same_node(crx.raw().raw()));
+    assert!(dependents[1].same_node(crx2.raw().raw()));
+    assert!(dependents[2].same_node(crx3.raw().raw()));
+
+    let dependents = g.transitive_dependents(crx2.raw().raw());
+    assert_eq!(dependents.len(), 1);
+    assert!(dependents[0].same_node(crx3.raw().raw()));
+
+    let dependents = g.transitive_dependents(other.raw().raw());
+    assert_eq!(dependents.len(), 1);
+    assert!(dependents[0].same_node(other_crx.raw().raw()));
+    assert!(g.transitive_dependents(crx3.raw().raw()).is_empty());
+}
+
+#[test]
+fn test_restricted_view() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let secret = g.new_crx(move |g| *var.get(g) * 3);
+    let g2 = RxDAG::new();
+    let other_var = g2.new_var(1);
+
+    let view = g.restricted_view(&[crx.raw().raw(), other_var.raw().raw()]);
+    assert_eq!(view.get(crx.raw()), Some(&2));
+    assert_eq!(view.get(secret.raw()), None);
+    assert_eq!(view.get(var.raw()), None);
+    assert!(!view.is_allowed(other_var.raw().raw()));
+
+    var.set(&g, 2);
+    g.recompute();
+    let view = g.restricted_view(&[crx.raw().raw()]);
+    assert_eq!(view.get(crx.raw()), Some(&4));
+}
+
+#[test]
+fn test_stats() {
+    let g = RxDAG::new();
+    assert_eq!(g.stats(), GraphStats::default());
+
+    let var = g.new_var(1);
+    let var2 = g.new_var(2);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let (_crx2, _crx3) = g.new_crx2(move |g| (*crx.get(g), *var2.get(g)));
+    g.run_crx(move |g| {
+        var.get(g);
+    });
+    let stats = g.stats();
+    assert_eq!(stats.node_count, 5);
+    assert_eq!(stats.edge_count, 3);
+    assert_eq!(stats.total_elements, 8);
+    assert!(stats.approx_bytes >= 5 * std::mem::size_of::<i32>());
+
+    let _ = g.new_var([0u8; 1024]);
+    assert!(g.stats().approx_bytes >= stats.approx_bytes + 1024);
+}
+
+#[test]
+fn test_dependencies_of() {
+    let g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(2);
+    let crx = g.new_crx(move |g| *var.get(g) + *var2.get(g));
+    let (crx2, crx3) = g.new_crx2(move |g| (*crx.get(g), *var.get(g)));
+
+    assert!(g.dependencies_of(var.raw().raw()).is_empty());
+    let dependencies = g.dependencies_of(crx.raw().raw());
+    assert_eq!(dependencies.len(), 2);
+    assert!(dependencies[0].same_node(var.raw().raw()));
+    assert!(dependencies[1].same_node(var2.raw().raw()));
+    let dependencies = g.dependencies_of(crx3.raw().raw());
+    assert_eq!(dependencies.len(), 2);
+    assert!(dependencies[0].same_node(var.raw().raw()));
+    assert!(dependencies[1].same_node(crx.raw().raw()));
+    assert_eq!(g.dependencies_of(crx2.raw().raw()).len(), 2);
+}
+
+#[test]
+fn test_explain_different_graph() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let g2 = RxDAG::new();
+    let other = g2.new_var("a");
+    g2.register_debug(other.raw());
+
+    // Rejected even though the policy ignores mismatches in release builds
+    g.set_mismatch_policy(MismatchPolicy::None);
+    assert!(catch_unwind(AssertUnwindSafe(|| g.register_debug(other.raw()))).is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| g.explain(other.raw().raw()))).is_err());
+    assert_eq!(g.explain(var.raw().raw()).node.value, None);
+}
+
+#[test]
+fn test_rediscover_deps() {
+    let use_second = Cell::new(false);
+    let use_second_ref = &use_second;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(2);
+    let crx = g.new_crx(move |g| if use_second_ref.get() { *var2.get(g) } else { *var.get(g) });
+    let dependencies = g.dependencies_of(crx.raw().raw());
+    assert_eq!(dependencies.len(), 1);
+    assert!(dependencies[0].same_node(var.raw().raw()));
+
+    // Non-reactive state changed, so the dependencies are stale until rediscovered
+    use_second.set(true);
+    var2.set(&g, 3);
+    assert_eq!(crx.get(g.now()), &1);
+    g.rediscover_deps(crx.raw().raw());
+    let dependencies = g.dependencies_of(crx.raw().raw());
+    assert_eq!(dependencies.len(), 1);
+    assert!(dependencies[0].same_node(var2.raw().raw()));
+    assert_eq!(crx.get(g.now()), &3);
+
+    var2.set(&g, 4);
+    assert_eq!(crx.get(g.now()), &4);
+}
+
+#[test]
+fn test_rediscover_deps_stateful() {
+    let runs = Cell::new(0);
+    let runs_ref = &runs;
+
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    // The rerun is observable, and its value is committed
+    let counter = g.new_crx(move |g| {
+        runs_ref.set(runs_ref.get() + 1);
+        *var.get(g) * 10 + runs_ref.get()
+    });
+    let source = g.new_crx(move |g| *var.get(g));
+    let delayed = g.new_delayed_crx(source);
+    let window = g.new_windowed_crx(var, 3, |values| values.iter().copied().collect::<Vec<_>>());
+    assert_eq!(counter.get(g.now()), &11);
+
+    g.rediscover_deps(counter.raw().raw());
+    assert_eq!(counter.get(g.now()), &12);
+
+    // Built-in stateful computations don't change when rerun
+    var.set(&g, 2);
+    g.recompute();
+    assert_eq!(delayed.get(g.stale()), &1);
+    g.rediscover_deps(delayed.raw().raw());
+    g.rediscover_deps(window.raw().raw());
+    g.recompute();
+    assert_eq!(delayed.get(g.stale()), &2);
+    assert_eq!(window.get(g.stale()), &vec![1, 2]);
+    g.rediscover_deps(delayed.raw().raw());
+    g.rediscover_deps(window.raw().raw());
+    g.recompute();
+    assert_eq!(delayed.get(g.stale()), &2);
+    assert_eq!(window.get(g.stale()), &vec![1, 2]);
+}
+
+#[test]
+fn test_compact() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let kept = g.new_owned_crx(move |g| *var.get(g) + 1);
+    let freed = g.new_owned_crx(move |g| vec![*var.get(g); 100]);
+    let freed_len = freed.clone();
+    let freed_len = g.new_owned_crx(move |g| freed_len.get(g).len());
+    let read_by_crx = g.new_owned_crx(move |g| *var.get(g) * 2);
+    let reader = read_by_crx.clone();
+    let crx = g.new_crx(move |g| *reader.get(g) + 1);
+    assert_eq!(*freed_len.get(g.now()), 100);
+
+    // Still has handles
+    assert_eq!(g.compact(), 0);
+    drop(freed);
+    // Still read by `freed_len`
+    assert_eq!(g.compact(), 0);
+    drop(freed_len);
+    drop(read_by_crx);
+    // `freed_len`, then `freed` which it read. `read_by_crx` is still read by `crx`
+    assert_eq!(g.compact(), 2);
+    assert_eq!(g.compact(), 0);
+
+    var.set(&g, 2);
+    assert_eq!(*kept.get(g.now()), 3);
+    assert_eq!(*crx.get(g.now()), 5);
+}
+
+#[test]
+fn test_constant_candidates() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let constant = g.new_crx(|_| 5);
+    let varying = g.new_crx(move |g| *var.get(g) * 2);
+    g.track_volatility(3);
+    for i in 0..3 {
+        assert!(g.constant_candidates().is_empty());
+        var.set(&g, i);
+        g.recompute();
+    }
+    let candidates = g.constant_candidates();
+    assert_eq!(candidates.len(), 1);
+    assert!(candidates[0].same_node(constant.raw().raw()));
+    for _ in 0..3 {
+        g.recompute();
+    }
+    let candidates = g.constant_candidates();
+    assert_eq!(candidates.len(), 2);
+    assert!(candidates[0].same_node(constant.raw().raw()));
+    assert!(candidates[1].same_node(varying.raw().raw()));
+}
+
+#[test]
+fn test_tree_string() {
+    let g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    let _crx3 = g.new_crx(move |g| crx2.get(g).to_string());
+    let vec_type = std::any::type_name::<Vec<i32>>();
+    let string_type = std::any::type_name::<String>();
+    assert_eq!(g.tree_string(), format!(
+        "crx#6: {string_type}\n  crx#4: i32\n    var#0: {vec_type}\n    crx#2: i32\n      var#0: {vec_type}\n"
+    ));
+}
+
+#[test]
+fn test_get_boxed() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(5);
+    let crx = g.new_crx(move |g| format!("{}", var.get(g)));
+    let unregistered = g.new_var(1);
+    g.register_dynamic(var.raw());
+    g.register_dynamic(crx.raw());
+
+    let value = g.get_boxed(var.raw().raw(), g.stale()).unwrap();
+    assert_eq!(value.downcast_ref::<i32>(), Some(&5));
+    assert!(value.downcast_ref::<String>().is_none());
+    var.set(&g, 6);
+    g.recompute();
+    let value = g.get_boxed(crx.raw().raw(), g.stale()).unwrap();
+    assert_eq!(*value.downcast::<String>().unwrap(), "6");
+    assert!(g.get_boxed(unregistered.raw().raw(), g.stale()).is_none());
+}
+
+#[test]
+fn test_get_boxed_different_graph() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    g.register_dynamic(var.raw());
+    let g2 = RxDAG::new();
+    let other = g2.new_var("a");
+
+    // Rejected even though the policy ignores mismatches in release builds
+    g.set_mismatch_policy(MismatchPolicy::None);
+    assert!(catch_unwind(AssertUnwindSafe(|| g.register_dynamic(other.raw()))).is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| g.get_boxed(other.raw().raw(), g.stale()))).is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| g.get_boxed(var.raw().raw(), g2.stale()))).is_err());
+    assert_eq!(g.get_boxed(var.raw().raw(), g.stale()).unwrap().downcast_ref::<i32>(), Some(&1));
+}
+
+#[test]
+fn test_get_at() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let crx = g.new_crx(move |g| *var.get(g) * 10);
+    g.track_history(crx.raw(), 3);
+    let start = g.revision();
+    for value in 2..=5 {
+        var.set(&g, value);
+        g.recompute();
+    }
+    // Revision without a change keeps the previous value
+    g.recompute();
+    let end = g.revision();
+    assert_eq!(g.get_at::<i32>(crx.raw().raw(), end), Some(50));
+    assert_eq!(g.get_at::<i32>(crx.raw().raw(), end - 1), Some(50));
+    assert_eq!(g.get_at::<i32>(crx.raw().raw(), start + 3), Some(40));
+    assert_eq!(g.get_at::<i32>(crx.raw().raw(), start + 2), Some(30));
+    // Evicted
+    assert_eq!(g.get_at::<i32>(crx.raw().raw(), start + 1), None);
+    assert_eq!(g.get_at::<i32>(crx.raw().raw(), end + 1), None);
+    assert_eq!(g.get_at::<i32>(var.raw().raw(), end), None);
+}
+
+#[test]
+fn test_history_not_a_dependency() {
+    let mut g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(2);
+    g.track_history(var.raw(), 2);
+    let crx = g.new_crx(move |g| *var2.get(g));
+    var.set(&g, 3);
+    g.recompute();
+    let crx2 = g.new_crx(move |g| *var2.get(g) + 1);
+    // Recording the tracked values didn't make `var` an input
+    for crx in [crx, crx2] {
+        let dependencies = g.dependencies_of(crx.raw().raw());
+        assert_eq!(dependencies.len(), 1);
+        assert!(dependencies[0].same_node(var2.raw().raw()));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_to_json() {
+    let g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    let _crx3 = g.new_crx(move |g| crx2.get(g).to_string());
+    let json = g.to_json();
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 4);
+    assert_eq!(json["nodes"][0], serde_json::json!({ "index": 0, "kind": "var" }));
+    assert_eq!(json["nodes"][1], serde_json::json!({ "index": 2, "kind": "crx" }));
+    assert_eq!(json["edges"], serde_json::json!([
+        { "index": 1, "inputs": [0], "outputs": [2] },
+        { "index": 3, "inputs": [0, 2], "outputs": [4] },
+        { "index": 5, "inputs": [4], "outputs": [6] }
+    ]));
+}
+
+#[test]
+fn test_find_redundant_edges() {
+    let g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(2);
+    let crx = g.new_crx(move |g| *var.get(g) * 2);
+    let _unrelated = g.new_crx(move |g| *var2.get(g) * 2);
+    let _crx2 = g.new_crx(move |g| *var.get(g) * 2);
+    let _both = g.new_crx(move |g| *var.get(g) + *var2.get(g));
+    let _derived = g.new_crx(move |g| *crx.get(g) + 1);
+    g.run_crx(move |g| { var.get(g); });
+    assert_eq!(g.find_redundant_edges(), vec![(2, 6)]);
+}
+
+#[test]
+fn test_critical_path_length() {
+    let g = RxDAG::new();
+    assert_eq!(g.critical_path_length(), 0);
+    let rx = g.new_var(vec![1, 2, 3]);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    let crx3 = g.new_crx(move |g| crx2.get(g).to_string());
+    let _short = g.new_crx(move |g| rx.get(g).len());
+    g.run_crx(move |g| { crx3.get(g); });
+    assert_eq!(g.critical_path_length(), 3);
+}
+
+#[test]
+fn test_edges() {
+    let g = RxDAG::new();
+    let var = g.new_var(1);
+    let var2 = g.new_var(2);
+    let (crx, _crx2) = g.new_crx2(move |g| (*var2.get(g) * 2, *var.get(g) + 1));
+    g.run_crx_tagged(7, move |g| { crx.get(g); });
+    assert_eq!(g.edges(), vec![
+        EdgeInfo { index: 2, inputs: vec![0, 1], num_outputs: 2, tag: None },
+        EdgeInfo { index: 5, inputs: vec![3], num_outputs: 0, tag: Some(7) }
+    ]);
+}
+
+#[test]
+fn test_explain() {
+    let mut g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    g.register_debug(rx.raw());
+    g.register_debug(crx2.raw());
+    rx.set(&g, vec![2, 3, 4]);
+    g.recompute();
+    let explanation = g.explain(crx2.raw().raw());
+    assert_eq!(explanation.node.value.as_deref(), Some("34"));
+    assert_eq!(explanation.inputs.len(), 2);
+    assert_eq!(explanation.inputs[0].value.as_deref(), Some("[2, 3, 4]"));
+    assert_eq!(explanation.inputs[1].value, None);
+    assert_eq!(explanation.to_string(), "crx#4: i32 = 34\n  var#0: alloc::vec::Vec<i32> = [2, 3, 4]\n  crx#2: i32\n");
+    // Explaining doesn't add dependencies
+    g.recompute();
+    assert_eq!(g.dependencies_of(crx2.raw().raw()).len(), 2);
+}
+
+#[test]
+fn test_epoch() {
+    let mut g = RxDAG::new();
+    let var1 = g.new_var(1);
+    let var2 = g.new_var(2);
+    let var3 = g.new_var(3);
+    let crx1 = g.new_crx(move |g| *var1.get(g) * 2);
+
+    g.begin_epoch();
+    var1.set(&g, 10);
+    g.recompute();
+    var2.set(&g, 20);
+    g.recompute();
+    let summary = g.end_epoch();
+
+    assert_eq!(summary.num_recomputes(), 2);
+    assert!(summary.did_change(var1.raw().raw()));
+    assert!(summary.did_change(var2.raw().raw()));
+    assert!(summary.did_change(crx1.raw().raw()));
+    assert!(!summary.did_change(var3.raw().raw()));
+    assert_eq!(summary.changed.len(), 3);
+
+    // Recomputes outside of an epoch aren't recorded
+    var3.set(&g, 30);
+    g.recompute();
+    g.begin_epoch();
+    assert!(g.end_epoch().changed.is_empty());
+}
+
+#[test]
+fn test_transitive_inputs() {
+    let g = RxDAG::new();
+    let rx = g.new_var(vec![1, 2, 3]);
+    let other = g.new_var(0);
+    let crx = g.new_crx(move |g| rx.get(g)[0] * 2);
+    let crx2 = g.new_crx(move |g| *crx.get(g) + rx.get(g)[1] * 10);
+    let crx3 = g.new_crx(move |g| crx2.get(g).to_string());
+    let _other_crx = g.new_crx(move |g| *other.get(g) + 1);
+
+    let inputs = g.transitive_inputs(crx3.raw().raw());
+    assert_eq!(inputs.len(), 3);
+    assert!(inputs[0].same_node(rx.raw().raw()));
+    assert!(inputs[1].same_node(crx.raw().raw()));
+    assert!(inputs[2].same_node(crx2.raw().raw()));
+
+    let vars = g.transitive_input_vars(crx3.raw().raw());
+    assert_eq!(vars.len(), 1);
+    assert!(vars[0].same_node(rx.raw().raw()));
+    assert!(g.transitive_inputs(rx.raw().raw()).is_empty());
+
+    // Other outputs of the same computation aren't inputs
+    let (first, _second) = g.new_crx2(move |g| (*other.get(g), *other.get(g) + 1));
+    let inputs = g.transitive_inputs(first.raw().raw());
+    assert_eq!(inputs.len(), 1);
+    assert!(inputs[0].same_node(other.raw().raw()));
+}
+
+#[test]
+fn test_last_read_map() {
+    let mut g = RxDAG::new();
+    let cond = g.new_var(true);
+    let a = g.new_var(1);
+    let b = g.new_var(2);
+    let crx = g.new_crx(move |g| if *cond.get(g) { *a.get(g) } else { *b.get(g) });
+    let edge_index = g.edges().last().unwrap().index;
+    let (cond_index, a_index, b_index) = (0, 1, 2);
+
+    assert_eq!(g.last_read_map()[&edge_index], vec![cond_index, a_index]);
+    cond.set(&g, false);
+    assert_eq!(*crx.get(g.now()), 2);
+    assert_eq!(g.last_read_map()[&edge_index], vec![cond_index, b_index]);
+    assert_eq!(g.last_read_map().len(), 1);
+}